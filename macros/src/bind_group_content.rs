@@ -67,6 +67,12 @@ fn parse_meta_own(attr: &syn::Attribute) -> syn::Meta {
     todo!()
 }
 
+///
+/// Generates the entries of a field.
+/// The visibility of the field can be overridden with either #[visibility(FRAGMENT | VERTEX)] or
+/// #[visibility = "wgpu::ShaderStages::FRAGMENT"], otherwise the visibility passed to entries is
+/// used.
+///
 pub fn generate_bind_group_entry(field: &syn::Field) -> proc_macro2::TokenStream{
     let visibility = field.attrs.iter().find(|a|{
         a.path.is_ident("visibility")
@@ -75,38 +81,52 @@ pub fn generate_bind_group_entry(field: &syn::Field) -> proc_macro2::TokenStream
 
     match visibility{
         Some(visibility) => {
-            let visibility: proc_macro2::TokenStream = visibility.parse_meta().map(|m|{
-                match m{
-                    syn::Meta::NameValue(n) => {
-                        if let syn::Lit::Str(i) = n.lit{
-                            let content: syn::Expr = i.parse().expect("Error not an Ident");
-                            quote!{
-                                #content
-                            }
-                        }
-                        else{
-                            panic!("Invalid literal provided");
-                        }
-                    },
-                    _ => quote!{wgpu::ShaderStages::all()},
-                }
-            }).unwrap_or_else(|_|{
-                quote!{
-                    wgpu::ShaderStages::all()
-                }
-            });
+            let visibility = parse_visibility(visibility);
             quote!{
                 ret.append(&mut <#ty>::entries(Some(#visibility)));
             }
         },
         None => {
             quote!{
-                ret.append(&mut <#ty>::entries(None));
+                ret.append(&mut <#ty>::entries(visibility));
             }
         }
     }
 }
 
+fn parse_visibility(attr: &syn::Attribute) -> proc_macro2::TokenStream{
+    let stages = attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Ident, syn::Token![|]>::parse_separated_nonempty
+    );
+    if let Ok(stages) = stages{
+        let stages = stages.iter();
+        return quote!{
+            #(wgpu::ShaderStages::#stages)|*
+        };
+    }
+
+    attr.parse_meta().map(|m|{
+        match m{
+            syn::Meta::NameValue(n) => {
+                if let syn::Lit::Str(i) = n.lit{
+                    let content: syn::Expr = i.parse().expect("Error not an Ident");
+                    quote!{
+                        #content
+                    }
+                }
+                else{
+                    panic!("Invalid literal provided");
+                }
+            },
+            _ => quote!{wgpu::ShaderStages::all()},
+        }
+    }).unwrap_or_else(|_|{
+        quote!{
+            wgpu::ShaderStages::all()
+        }
+    })
+}
+
 pub fn generate_bind_group_content_resource(field: &syn::Field) -> proc_macro2::TokenStream{
     let ident = &field.ident;
     quote!{
@@ -107,14 +107,51 @@ pub fn derive_vert(tokens: TokenStream) -> TokenStream{
     generate_vert(ast).into()
 }
 
+///
+/// A Macro for deriving the VertLayout of a vertex from a struct.
+/// Equivalent to Vert but also accepts locations of the form #[location(n)].
+///
+/// The VertexFormat of every field is inferred from its type, [u8; n] and [i8; n] as well as
+/// [u16; n] and [i16; n] are normalized if the field has the #[norm] attribute.
+/// The offsets are computed by adding up the sizes of the fields, which is correct since
+/// bytemuck::Pod does not allow padding.
+///
+/// ```
+/// #[repr(C)]
+/// #[derive(Copy, Clone, bytemuck::Zeroable, bytemuck::Pod, VertexLayout)]
+/// struct Vert{
+///     #[location(0)]
+///     pub pos: [f32; 3],
+///     #[location(1)]
+///     pub uv: [f32; 2],
+///     #[location(2)]
+///     #[norm]
+///     pub color: [u8; 4],
+/// }
+///
+/// let layout = Vert::buffer_layout();
+/// ```
+///
+#[proc_macro_derive(VertexLayout, attributes(location, norm))]
+pub fn derive_vertex_layout(tokens: TokenStream) -> TokenStream{
+    let ast: syn::DeriveInput = syn::parse(tokens).unwrap();
+
+    generate_vert(ast).into()
+}
+
 ///
 /// A macro to derive the BindGroupContent trait from a struct whos fields implement
 /// BindGroupContent.
 ///
+/// The entries and resources of the fields are appended in field order.
+/// Fields use the visibility passed to entries unless it is overridden with a visibility
+/// attribute.
+///
 /// ```
 /// #[derive(BindGroupContent)]
 /// struct TestBindGroupContent{
 ///     indices: Buffer<u32>,
+///     #[visibility(VERTEX | FRAGMENT)]
 ///     other_indices: Buffer<u64>,
 /// }
 /// ```
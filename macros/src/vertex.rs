@@ -78,15 +78,22 @@ pub fn generate_vertex_attributes(field: &syn::Field) -> proc_macro2::TokenStrea
 
     let meta = location_attr.parse_meta().unwrap_or_else(|_| panic!("Field {} does not have a attribute that conforms to structured format.", field_name));
 
-    let location_value: u32 = match meta{
-        syn::Meta::NameValue(meta_named_value) => {
-            if let syn::Lit::Int(i) = meta_named_value.lit{
-                i.base10_parse().unwrap_or_else(|_| panic!("Field {} location attribute value must be a base10 integer", field_name))
-            }else{
-                panic!("Field {} location attribute value must be an integer", field_name);
+    // Accepts both #[location = n] and #[location(n)].
+    let location_lit = match meta{
+        syn::Meta::NameValue(meta_named_value) => meta_named_value.lit,
+        syn::Meta::List(meta_list) if meta_list.nested.len() == 1 => {
+            match meta_list.nested.into_iter().next(){
+                Some(syn::NestedMeta::Lit(lit)) => lit,
+                _ => panic!("Field {} location attribute value must be an integer", field_name),
             }
         }
-        _ => panic!("Field {} location attribute value must be a string literal", field_name),
+        _ => panic!("Field {} location attribute has to be of the form #[location = n] or #[location(n)]", field_name),
+    };
+
+    let location_value: u32 = if let syn::Lit::Int(i) = location_lit{
+        i.base10_parse().unwrap_or_else(|_| panic!("Field {} location attribute value must be a base10 integer", field_name))
+    }else{
+        panic!("Field {} location attribute value must be an integer", field_name);
     };
 
     let norm_attr = field.attrs.iter().any(|x| x.path.is_ident("norm"));
@@ -118,7 +125,7 @@ pub fn format(field: &syn::Field, norm: bool) -> proc_macro2::TokenStream{
             if type_path.path.is_ident("f32"){
                 return quote!{Float32};
             }
-            if type_path.path.is_ident("u64"){
+            if type_path.path.is_ident("f64"){
                 return quote!{Float64};
             }
         }
@@ -67,6 +67,17 @@ pub trait BindGroupContent: Sized {
     fn create_bind_group(&self, device: &wgpu::Device) -> BindGroup<Self>{
         let layout =
             Self::create_bind_group_layout(device, None);
+        let bind_group = self.create_wgpu_bind_group(device, &layout);
+        BindGroup{
+            bind_group_layout: layout,
+            bind_group,
+            _ty: PhantomData,
+        }
+    }
+    ///
+    /// Creates a wgpu::BindGroup from the current resources using an existing layout.
+    ///
+    fn create_wgpu_bind_group(&self, device: &wgpu::Device, layout: &BindGroupLayoutWithDesc) -> wgpu::BindGroup{
         let resources = self.resources();
 
         let entries: Vec<wgpu::BindGroupEntry> = resources
@@ -78,16 +89,11 @@ pub trait BindGroupContent: Sized {
             })
             .collect();
 
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: None,
             entries: &entries,
             layout: &layout.layout,
-        });
-        BindGroup{
-            bind_group_layout: layout,
-            bind_group,
-            _ty: PhantomData,
-        }
+        })
     }
     fn create_bind_group_layout(
         device: &wgpu::Device,
@@ -114,6 +120,126 @@ pub trait BindGroupContent: Sized {
     }
 }
 
+///
+/// Creates a BindGroup and its layout from a list of resources for one-off use.
+///
+/// The bindings are assigned in the order of the entries starting from 0.
+///
+pub fn quick_bind_group(
+    device: &wgpu::Device,
+    entries: &[(wgpu::ShaderStages, wgpu::BindingType, wgpu::BindingResource)],
+) -> (BindGroupLayoutWithDesc, wgpu::BindGroup) {
+    let layout_entries: Vec<wgpu::BindGroupLayoutEntry> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (visibility, ty, _))| wgpu::BindGroupLayoutEntry {
+            binding: i as u32,
+            visibility: *visibility,
+            ty: *ty,
+            count: None,
+        })
+        .collect();
+
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        entries: &layout_entries,
+        label: None,
+    });
+
+    let bind_group_entries: Vec<wgpu::BindGroupEntry> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (_, _, resource))| wgpu::BindGroupEntry {
+            binding: i as u32,
+            resource: resource.clone(),
+        })
+        .collect();
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        entries: &bind_group_entries,
+        layout: &layout,
+    });
+
+    (
+        BindGroupLayoutWithDesc {
+            layout,
+            entries: layout_entries,
+        },
+        bind_group,
+    )
+}
+
+///
+/// A builder for a BindGroupLayoutWithDesc.
+///
+/// The binding indices are assigned in the order the entries are pushed starting from 0.
+///
+/// ```ignore
+/// let layout = BindGroupLayoutBuilder::new()
+///     .push_uniform(wgpu::ShaderStages::VERTEX)
+///     .push_texture(wgpu::ShaderStages::FRAGMENT)
+///     .push_sampler(wgpu::ShaderStages::FRAGMENT)
+///     .build(&gpu.device, None);
+/// ```
+///
+#[derive(Default)]
+pub struct BindGroupLayoutBuilder {
+    entries: Vec<wgpu::BindGroupLayoutEntry>,
+}
+
+impl BindGroupLayoutBuilder {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push_entry(
+        mut self,
+        visibility: wgpu::ShaderStages,
+        ty: wgpu::BindingType,
+        count: Option<std::num::NonZeroU32>,
+    ) -> Self {
+        self.entries.push(wgpu::BindGroupLayoutEntry {
+            binding: self.entries.len() as u32,
+            visibility,
+            ty,
+            count,
+        });
+        self
+    }
+
+    #[inline]
+    pub fn push_buffer(self, visibility: wgpu::ShaderStages, read_only: bool) -> Self {
+        self.push_entry(visibility, wgsl::buffer(read_only), None)
+    }
+
+    #[inline]
+    pub fn push_uniform(self, visibility: wgpu::ShaderStages) -> Self {
+        self.push_entry(visibility, wgsl::uniform(), None)
+    }
+
+    #[inline]
+    pub fn push_texture(self, visibility: wgpu::ShaderStages) -> Self {
+        self.push_entry(visibility, wgsl::texture_2d(), None)
+    }
+
+    #[inline]
+    pub fn push_sampler(self, visibility: wgpu::ShaderStages) -> Self {
+        self.push_entry(visibility, wgsl::sampler(), None)
+    }
+
+    pub fn build(self, device: &wgpu::Device, label: wgpu::Label) -> BindGroupLayoutWithDesc {
+        BindGroupLayoutWithDesc {
+            layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                entries: &self.entries,
+                label,
+            }),
+            entries: self.entries,
+        }
+    }
+}
+
 // TODO: Derive macro for BindGroupContent.
 
 macro_rules! bind_group_content_for_tuple{
@@ -191,6 +317,16 @@ impl<C: BindGroupContent> Bound<C> {
     pub fn update(&mut self, device: &wgpu::Device) {
         self.bind_group.update(&self.content, device);
     }
+
+    ///
+    /// Replaces the content, for example with a resized texture, and recreates the bind group
+    /// with the same layout. Returns the previous content.
+    ///
+    pub fn replace(&mut self, content: C, device: &wgpu::Device) -> C {
+        let content = std::mem::replace(&mut self.content, content);
+        self.update(device);
+        content
+    }
 }
 
 impl<C: BindGroupContent> CreateBindGroupLayout for Bound<C> {
@@ -237,8 +373,11 @@ pub struct BindGroup<C: BindGroupContent>{
 }
 
 impl<C: BindGroupContent> BindGroup<C>{
-    pub fn update(&mut self, conent: &C, device: &wgpu::Device) {
-        *self = conent.create_bind_group(device)
+    ///
+    /// Recreates the bind group from the current resources of content keeping the layout.
+    ///
+    pub fn update(&mut self, content: &C, device: &wgpu::Device) {
+        self.bind_group = content.create_wgpu_bind_group(device, &self.bind_group_layout);
     }
 }
 
@@ -419,6 +558,10 @@ pub mod wgsl {
         wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
     }
 
+    pub fn sampler_comparison() -> wgpu::BindingType {
+        wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison)
+    }
+
     pub fn texture_2d() -> wgpu::BindingType {
         wgpu::BindingType::Texture {
             sample_type: wgpu::TextureSampleType::Float { filterable: true },
@@ -426,4 +569,39 @@ pub mod wgsl {
             multisampled: false,
         }
     }
+
+    pub fn texture_2d_array() -> wgpu::BindingType {
+        wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2Array,
+            multisampled: false,
+        }
+    }
+
+    pub fn texture_3d() -> wgpu::BindingType {
+        wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D3,
+            multisampled: false,
+        }
+    }
+
+    pub fn texture_storage_2d(
+        format: wgpu::TextureFormat,
+        access: wgpu::StorageTextureAccess,
+    ) -> wgpu::BindingType {
+        wgpu::BindingType::StorageTexture {
+            access,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            format,
+        }
+    }
+
+    pub fn texture_depth_2d() -> wgpu::BindingType {
+        wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Depth,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        }
+    }
 }
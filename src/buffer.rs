@@ -3,6 +3,7 @@ use wgpu::util::DeviceExt;
 use std::{marker::PhantomData, ops::{Deref, DerefMut, RangeBounds, Range}};
 use std::mem::ManuallyDrop;
 use crate::utils::*;
+use anyhow::{Result, ensure};
 
 use super::binding;
 
@@ -333,6 +334,8 @@ impl<'bb, C: bytemuck::Pod> BufferBuilder<'bb, C>{
     ///
     /// Build a buffer with data.
     ///
+    /// Panics if data is empty.
+    ///
     pub fn build(&self, device: &wgpu::Device, data: &[C]) -> Buffer<C>{
         Buffer::<C>::new(
             device, 
@@ -345,9 +348,57 @@ impl<'bb, C: bytemuck::Pod> BufferBuilder<'bb, C>{
     ///
     /// Build a buffer with length. Data in the buffer is undefined.
     ///
+    /// Panics if len is zero.
+    ///
     pub fn build_empty(&self, device: &wgpu::Device, len: usize) -> Buffer<C>{
         Buffer::<C>::new_empty(device, self.usages, self.label, len)
     }
+
+    ///
+    /// Checks the usages against the rules of wgpu.
+    ///
+    /// The usages have to be non empty and without the MAPPABLE_PRIMARY_BUFFERS feature MAP_READ
+    /// can only be combined with COPY_DST and MAP_WRITE only with COPY_SRC.
+    ///
+    pub fn validate(&self, device: &wgpu::Device) -> Result<()>{
+        ensure!(!self.usages.is_empty(), "Buffer {:?} has no usages", self.label);
+
+        if !device.features().contains(wgpu::Features::MAPPABLE_PRIMARY_BUFFERS){
+            if self.usages.contains(wgpu::BufferUsages::MAP_READ){
+                ensure!(
+                    (wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST).contains(self.usages),
+                    "Buffer {:?} with MAP_READ can only have the COPY_DST usage, got {:?}", self.label, self.usages
+                );
+            }
+            if self.usages.contains(wgpu::BufferUsages::MAP_WRITE){
+                ensure!(
+                    (wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC).contains(self.usages),
+                    "Buffer {:?} with MAP_WRITE can only have the COPY_SRC usage, got {:?}", self.label, self.usages
+                );
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Like build but returns an error instead of panicking if data is empty or the usages are
+    /// invalid (see validate).
+    ///
+    pub fn try_build(&self, device: &wgpu::Device, data: &[C]) -> Result<Buffer<C>>{
+        ensure!(!data.is_empty(), "Buffer {:?} can not be created from empty data", self.label);
+        self.validate(device)?;
+        Ok(self.build(device, data))
+    }
+
+    ///
+    /// Like build_empty but returns an error instead of panicking if len is zero or the usages
+    /// are invalid (see validate).
+    ///
+    pub fn try_build_empty(&self, device: &wgpu::Device, len: usize) -> Result<Buffer<C>>{
+        ensure!(len > 0, "Buffer {:?} can not be created with length zero", self.label);
+        self.validate(device)?;
+        Ok(self.build_empty(device, len))
+    }
 }
 
 // TODO: std140 and std430
@@ -367,7 +418,13 @@ pub struct Buffer<C: bytemuck::Pod>{
 }
 
 impl<C: bytemuck::Pod> Buffer<C>{
+    ///
+    /// Creates a Buffer with len elements. Data in the buffer is undefined.
+    ///
+    /// Panics if len is zero.
+    ///
     pub fn new_empty(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label, len: usize) -> Self{
+        assert!(len > 0, "Cannot create a Buffer with zero elements (label: {:?})", label);
         let buffer = device.create_buffer(&wgpu::BufferDescriptor{
             label,
             size: (len * std::mem::size_of::<C>()) as u64,
@@ -386,7 +443,13 @@ impl<C: bytemuck::Pod> Buffer<C>{
         }
     }
 
+    ///
+    /// Creates a Buffer initialized with data.
+    ///
+    /// Panics if data is empty.
+    ///
     pub fn new(device: &wgpu::Device, usage: wgpu::BufferUsages, label: wgpu::Label, data: &[C]) -> Self{
+        assert!(!data.is_empty(), "Cannot create a Buffer with zero elements (label: {:?})", label);
         let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor{
             label,
             contents: bytemuck::cast_slice(data),
@@ -576,6 +639,103 @@ impl<C: bytemuck::Pod> Buffer<C>{
         *self = Buffer::<C>::new_empty(device, self.usage, label, len);
     }
 
+    ///
+    /// Reads the content of the buffer back to the cpu.
+    ///
+    /// If the buffer does not have the MAP_READ usage its content is copied to a staging buffer
+    /// first, which requires the COPY_SRC usage.
+    ///
+    pub async fn read_vec(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Vec<C>>{
+        if self.usage.contains(wgpu::BufferUsages::MAP_READ){
+            return Ok(self.slice(..).map_async_poll(device).await.to_vec());
+        }
+
+        ensure!(
+            self.usage.contains(wgpu::BufferUsages::COPY_SRC),
+            "Buffer {:?} can neither be mapped nor copied for reading", self.label
+        );
+
+        let mut staging = BufferBuilder::<C>::new()
+            .copy_dst().read()
+            .set_label(Some("buffer_readback"))
+            .build_empty(device, self.len);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("buffer_readback")});
+        self.slice(..).copy_to_buffer(&mut staging, 0, &mut encoder);
+        queue.submit(Some(encoder.finish()));
+
+        let view = staging.slice(..).map_async_poll(device).await;
+        Ok(view.to_vec())
+    }
+
+    ///
+    /// Grows the buffer to hold at least new_capacity elements.
+    ///
+    /// If new_capacity exceeds the current length a new buffer with the same usage and label is
+    /// allocated and the old content is copied to its start. This requires the COPY_SRC and
+    /// COPY_DST usages. Does nothing if the buffer is already large enough.
+    ///
+    pub fn reserve(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, new_capacity: usize) -> Result<()>{
+        if new_capacity <= self.len{
+            return Ok(());
+        }
+
+        ensure!(
+            self.usage.contains(wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST),
+            "Buffer {:?} needs the COPY_SRC and COPY_DST usages to be resized", self.label
+        );
+        ensure!(
+            self.size() as wgpu::BufferAddress % wgpu::COPY_BUFFER_ALIGNMENT == 0,
+            "The size {} of buffer {:?} is not aligned to {} bytes", self.size(), self.label, wgpu::COPY_BUFFER_ALIGNMENT
+        );
+
+        let buffer = Self::new_empty(device, self.usage, self.label.as_deref(), new_capacity);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("buffer_reserve")});
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer.buffer, 0, self.size() as wgpu::BufferAddress);
+        queue.submit(Some(encoder.finish()));
+
+        *self = buffer;
+        Ok(())
+    }
+
+    ///
+    /// Writes data to the buffer starting at the element offset.
+    ///
+    /// Returns an error instead of panicking if the data does not fit into the buffer, the buffer
+    /// does not have the COPY_DST usage or the byte range is not aligned to
+    /// wgpu::COPY_BUFFER_ALIGNMENT.
+    ///
+    pub fn write(&self, queue: &wgpu::Queue, offset: usize, data: &[C]) -> Result<()>{
+        let (offset, _) = self.validate_write(offset, data.len())?;
+
+        queue.write_buffer(&self.buffer, offset, bytemuck::cast_slice(data));
+        Ok(())
+    }
+
+    ///
+    /// Checks that len elements can be written to the buffer at the element offset and returns
+    /// the offset and size of the write in bytes (see write).
+    ///
+    pub fn validate_write(&self, offset: usize, len: usize) -> Result<(wgpu::BufferAddress, wgpu::BufferAddress)>{
+        ensure!(
+            offset + len <= self.len,
+            "Writing {} elements at offset {} exceeds the length {} of buffer {:?}", len, offset, self.len, self.label
+        );
+        ensure!(
+            self.usage.contains(wgpu::BufferUsages::COPY_DST),
+            "Buffer {:?} does not have the COPY_DST usage", self.label
+        );
+
+        let offset = (offset * std::mem::size_of::<C>()) as wgpu::BufferAddress;
+        let size = (len * std::mem::size_of::<C>()) as wgpu::BufferAddress;
+        ensure!(
+            offset % wgpu::COPY_BUFFER_ALIGNMENT == 0 && size % wgpu::COPY_BUFFER_ALIGNMENT == 0,
+            "Offset {} and size {} have to be aligned to {} bytes", offset, size, wgpu::COPY_BUFFER_ALIGNMENT
+        );
+        Ok((offset, size))
+    }
+
     // TODO: maybe move to slice.
     pub fn write_buffer(&mut self, queue: &wgpu::Queue, offset: usize, data: &[C]){
         queue.write_buffer(&self.buffer, (offset * std::mem::size_of::<C>()) as u64, bytemuck::cast_slice(data));
@@ -685,3 +845,137 @@ impl IndexFormat for Buffer<u16>{
         wgpu::IndexFormat::Uint16
     }
 }
+
+///
+/// A index buffer of u16 or u32 indices that keeps track of its IndexFormat.
+///
+#[derive(DerefMut)]
+pub struct IndexBuffer<I: bytemuck::Pod>{
+    #[target]
+    buffer: Buffer<I>,
+}
+
+impl<I: bytemuck::Pod> IndexBuffer<I> where Buffer<I>: IndexFormat{
+    pub fn new(device: &wgpu::Device, label: wgpu::Label, indices: &[I]) -> Self{
+        Self{
+            buffer: Buffer::new_index(device, label, indices),
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> wgpu::IndexFormat{
+        self.buffer.get_index_format()
+    }
+
+    ///
+    /// Sets this buffer as the index buffer of the pass with the matching format.
+    ///
+    pub fn bind<'rp>(&'rp self, pass: &mut crate::RenderPassPipeline<'rp, '_>){
+        pass.render_pass.render_pass.set_index_buffer(self.buffer.slice(..).into(), self.format());
+    }
+
+    ///
+    /// Binds the buffer and draws all of its indices.
+    ///
+    pub fn draw_indexed_all<'rp>(&'rp self, pass: &mut crate::RenderPassPipeline<'rp, '_>, base_vertex: i32, instances: Range<u32>){
+        self.bind(pass);
+        pass.draw_indexed(0..(self.buffer.len() as u32), base_vertex, instances);
+    }
+}
+
+///
+/// A vertex buffer stepped per instance that remembers the vertex buffer slot it is bound to.
+///
+#[derive(DerefMut)]
+pub struct InstanceBuffer<I: crate::VertLayout>{
+    #[target]
+    buffer: Buffer<I>,
+    slot: u32,
+}
+
+impl<I: crate::VertLayout> InstanceBuffer<I>{
+    pub fn new(device: &wgpu::Device, label: wgpu::Label, instances: &[I], slot: u32) -> Self{
+        Self{
+            buffer: BufferBuilder::new()
+                .vertex().copy_dst()
+                .set_label(label)
+                .build(device, instances),
+            slot,
+        }
+    }
+
+    #[inline]
+    pub fn slot(&self) -> u32{
+        self.slot
+    }
+
+    ///
+    /// Sets this buffer as the instance buffer of its slot.
+    ///
+    pub fn bind<'rp>(&'rp self, pass: &mut crate::RenderPassPipeline<'rp, '_>){
+        pass.set_instance_buffer(self.slot, self.buffer.slice(..));
+    }
+}
+
+///
+/// A wrapper for wgpu::util::StagingBelt writing typed data to buffers through reused staging
+/// buffers.
+///
+/// Every frame write has to be followed by finish before the encoder is submitted and recall
+/// after the submission, so that the staging buffers can be reused.
+///
+/// ```ignore
+/// belt.write_buffer(&gpu.device, &mut encoder, &vertices, 0, &data)?;
+/// belt.finish();
+/// gpu.queue.submit(Some(encoder.finish()));
+/// pollster::block_on(belt.recall());
+/// ```
+///
+pub struct StagingBelt{
+    belt: wgpu::util::StagingBelt,
+}
+
+impl StagingBelt{
+    ///
+    /// Creates a belt allocating staging buffers of chunk_size bytes.
+    /// Writes larger than chunk_size get their own staging buffer.
+    ///
+    pub fn new(chunk_size: wgpu::BufferAddress) -> Self{
+        Self{
+            belt: wgpu::util::StagingBelt::new(chunk_size),
+        }
+    }
+
+    ///
+    /// Records a copy of data to target at offset (in elements) into the encoder.
+    ///
+    /// Returns an error under the same conditions as Buffer::write (see Buffer::validate_write).
+    ///
+    pub fn write_buffer<C: bytemuck::Pod>(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, target: &Buffer<C>, offset: usize, data: &[C]) -> Result<()>{
+        let (offset, size) = target.validate_write(offset, data.len())?;
+
+        let size = match wgpu::BufferSize::new(size){
+            Some(size) => size,
+            None => return Ok(()),
+        };
+
+        self.belt.write_buffer(encoder, &target.buffer, offset, size, device)
+            .copy_from_slice(bytemuck::cast_slice(data));
+        Ok(())
+    }
+
+    ///
+    /// Closes the staging buffers written to. Has to be called before submitting the encoders.
+    ///
+    pub fn finish(&mut self){
+        self.belt.finish();
+    }
+
+    ///
+    /// Returns the staging buffers for reuse once the gpu is done with them.
+    /// Has to be called after submitting. The future only resolves when the device is polled.
+    ///
+    pub fn recall(&mut self) -> impl std::future::Future<Output = ()> + Send{
+        self.belt.recall()
+    }
+}
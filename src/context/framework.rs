@@ -0,0 +1,84 @@
+use crate::*;
+use winit::{
+    event::*,
+    event_loop::{ControlFlow, EventLoop},
+};
+
+///
+/// State of an application run by the Framework.
+///
+pub trait State: 'static{
+    ///
+    /// Called on every RedrawRequested event with a view of the current surface texture.
+    ///
+    fn render(&mut self, winit: &mut WinitContext, view: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder) -> Result<(), wgpu::SurfaceError>;
+
+    ///
+    /// Called after the surface has been resized.
+    ///
+    fn resize(&mut self, _winit: &mut WinitContext, _size: winit::dpi::PhysicalSize<u32>){}
+
+    ///
+    /// Called for every event before it is handled by the Framework.
+    ///
+    fn event(&mut self, _winit: &mut WinitContext, _event: &Event<()>){}
+}
+
+///
+/// Drives a winit event loop for a State without requiring imgui.
+///
+/// ```ignore
+///
+/// struct App;
+///
+/// impl State for App{
+///     fn render(&mut self, winit: &mut WinitContext, view: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder) -> Result<(), wgpu::SurfaceError>{
+///         Ok(())
+///     }
+/// }
+///
+/// let event_loop = EventLoop::new();
+///
+/// let window = WindowBuilder::new()
+///     .with_inner_size(winit::dpi::LogicalSize::new(800, 600))
+///     .build(&event_loop).unwrap();
+///
+/// let winit = GPUContextBuilder::new()
+///     .set_features_util()
+///     .build_winit_context(window);
+///
+/// Framework::new(winit, App).run(event_loop);
+///
+/// ```
+///
+pub struct Framework<S: State>{
+    pub winit: WinitContext,
+    pub state: S,
+}
+
+impl<S: State> Framework<S>{
+    pub fn new(winit: WinitContext, state: S) -> Self{
+        Self{
+            winit,
+            state,
+        }
+    }
+
+    pub fn run(self, event_loop: EventLoop<()>) -> !{
+        let Self{mut winit, mut state} = self;
+
+        event_loop.run(move |event, _, control_flow|{
+            state.event(&mut winit, &event);
+
+            let mut winit = winit.handle_events(&event, control_flow);
+
+            winit.on_resize(&event, |winit, size|{
+                state.resize(winit, size);
+            });
+
+            winit.on_redraw_encode(&event, control_flow, |winit, view, encoder, _event, _control_flow|{
+                state.render(winit, view, encoder)
+            });
+        })
+    }
+}
@@ -125,6 +125,14 @@ impl<'gcb> GPUContextBuilder<'gcb>{
         self
     }
 
+    ///
+    /// Sets the backends the instance is created with in build and WinitContextBuilder::build.
+    ///
+    pub fn set_backends(mut self, backends: wgpu::Backends) -> Self{
+        self.backends = backends;
+        self
+    }
+
     pub fn set_device_label(mut self, label: wgpu::Label<'gcb>) -> Self{
         self.device_descriptor.label = label;
         self
@@ -144,13 +152,18 @@ impl<'gcb> GPUContextBuilder<'gcb>{
             None,
         ).await.unwrap();
 
+        let timer = ScopedTimer::for_device(&device, &queue);
+
         GPUContext{
             device,
             queue,
             adapter,
             instance,
+            timer,
+            start: Instant::now(),
             time: Instant::now(),
             dt: Duration::from_secs(1),
+            fps: 0.,
         }
     } 
 
@@ -179,13 +192,18 @@ impl<'gcb> GPUContextBuilder<'gcb>{
             None,
         ).await.unwrap();
 
+        let timer = ScopedTimer::for_device(&device, &queue);
+
         GPUContext{
             device,
             queue,
             adapter,
             instance,
+            timer,
+            start: Instant::now(),
             time: Instant::now(),
             dt: Duration::from_secs(1),
+            fps: 0.,
         }
     }
 
@@ -196,8 +214,15 @@ pub struct GPUContext{
     pub queue: wgpu::Queue,
     pub adapter: wgpu::Adapter,
     pub instance: wgpu::Instance,
+    ///
+    /// Timer for ComputePassPipeline::timed_dispatch.
+    /// Only available if the device supports the TIMESTAMP_QUERY feature.
+    ///
+    pub timer: Option<ScopedTimer>,
+    pub start: Instant,
     pub time: Instant,
     pub dt: Duration,
+    fps: f32,
 }
 
 impl GPUContext{
@@ -205,6 +230,22 @@ impl GPUContext{
         pollster::block_on(Self::new_async(instance, surface))
     }
 
+    ///
+    /// Creates a context without a window or surface for example for tests or compute only
+    /// applications.
+    ///
+    /// Like GPUContext::new this requests the TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+    /// VERTEX_WRITABLE_STORAGE, PUSH_CONSTANTS, MAPPABLE_PRIMARY_BUFFERS and POLYGON_MODE_LINE
+    /// features with a max_push_constant_size of 128 bytes on any backend.
+    ///
+    pub fn headless() -> Self{
+        pollster::block_on(Self::headless_async())
+    }
+
+    pub async fn headless_async() -> Self{
+        Self::new_async(wgpu::Instance::new(wgpu::Backends::all()), None).await
+    }
+
     pub async fn new_async(instance: wgpu::Instance, surface: Option<&wgpu::Surface>) -> Self{
         //let instance = wgpu::Instance::new(wgpu::Backends::all());
 
@@ -235,20 +276,78 @@ impl GPUContext{
             None,
         ).await.unwrap();
 
+        let timer = ScopedTimer::for_device(&device, &queue);
+
         // DT is initialized with 1 second for first frame
         Self{
             device,
             queue,
             adapter,
             instance,
+            timer,
+            start: Instant::now(),
             time: Instant::now(),
             dt: Duration::from_secs(1),
+            fps: 0.,
+        }
+    }
+    ///
+    /// Returns a multisample count that can be used with a render attachment of this format.
+    ///
+    /// wgpu 0.12 has no query for multisample support, and it only accepts sample counts of 1 and 4.
+    /// This returns 4 for renderable formats with a filterable float or depth sample type.
+    /// It returns 1 for everything else, including 32 bit float and integer formats.
+    ///
+    pub fn max_sample_count(&self, format: wgpu::TextureFormat) -> u32{
+        let features = self.adapter.get_texture_format_features(format);
+        let renderable = features.allowed_usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+        let multisampled = matches!(
+            format.describe().sample_type,
+            wgpu::TextureSampleType::Float{filterable: true} | wgpu::TextureSampleType::Depth
+        );
+        if renderable && multisampled{
+            4
         }
+        else{
+            1
+        }
+    }
+
+    ///
+    /// Returns the time elapsed between the creation of the context and the start of the current
+    /// frame.
+    ///
+    /// In contrast to Instant::elapsed this stays constant during a frame so that everything
+    /// rendered in one frame is animated with the same time.
+    ///
+    #[inline]
+    pub fn elapsed(&self) -> Duration{
+        self.time - self.start
     }
+
+    ///
+    /// Returns the duration of the last frame.
+    ///
+    #[inline]
+    pub fn delta_time(&self) -> Duration{
+        self.dt
+    }
+
+    ///
+    /// Returns the frames per second as exponential moving average over the recent frames.
+    ///
+    #[inline]
+    pub fn fps(&self) -> f32{
+        self.fps
+    }
+
     pub(crate) fn update(&mut self) {
         let time = Instant::now();
         self.dt = time - self.time;
         self.time = time;
+
+        let fps = 1. / self.dt.as_secs_f32().max(f32::EPSILON);
+        self.fps = if self.fps == 0. {fps} else {self.fps * 0.95 + fps * 0.05};
     }
     pub fn encode<F>(&mut self, mut f: F)
         where F: FnMut(&mut GPUContext, &mut wgpu::CommandEncoder){
@@ -257,7 +356,57 @@ impl GPUContext{
 
             f(self, &mut encoder);
 
-            self.queue.submit(Some(encoder.finish()));
+            self.submit_timed(encoder);
+    }
+    ///
+    /// Resolves the timestamps of the timer, submits the encoder and updates the timings with
+    /// the frames that have been read back in the meantime (see GPUContext::timings).
+    /// Does not wait for the gpu to finish the submission.
+    ///
+    /// Used by encode and WinitContext::encode, encoders submitted with submit are not timed.
+    ///
+    pub fn submit_timed(&mut self, mut encoder: wgpu::CommandEncoder) -> wgpu::SubmissionIndex{
+        if let Some(timer) = &self.timer{
+            timer.resolve(&mut encoder);
+        }
+
+        let index = self.queue.submit(Some(encoder.finish()));
+
+        if let Some(timer) = &mut self.timer{
+            timer.collect(&self.device);
+        }
+        index
+    }
+    ///
+    /// Finishes and submits the encoder returning the index of the submission.
+    ///
+    pub fn submit(&self, encoder: wgpu::CommandEncoder) -> wgpu::SubmissionIndex{
+        self.queue.submit(Some(encoder.finish()))
+    }
+    ///
+    /// Blocks until the submission with index has been completed.
+    ///
+    /// wgpu 0.12 can only wait for all submissions, so this also waits for submissions made
+    /// after index.
+    ///
+    pub fn wait_for(&self, _index: wgpu::SubmissionIndex){
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+    ///
+    /// Returns the smoothed durations of all dispatches timed with
+    /// ComputePassPipeline::timed_dispatch in milliseconds.
+    ///
+    /// Empty if the device does not support the TIMESTAMP_QUERY feature.
+    ///
+    pub fn timings(&self) -> Vec<(String, f32)>{
+        self.timer.as_ref().map(|timer| timer.timings()).unwrap_or_default()
+    }
+    ///
+    /// Records several passes into the encoder one after another (see PassRecorder).
+    ///
+    pub fn passes<F>(&self, encoder: &mut wgpu::CommandEncoder, f: F)
+        where F: FnOnce(&mut PassRecorder){
+            f(&mut PassRecorder::new(encoder));
     }
     pub fn encode_img<F>(&mut self, size: [u32; 2], mut f: F) -> image::DynamicImage
         where F: FnMut(&mut GPUContext, &wgpu::TextureView, &mut wgpu::CommandEncoder)
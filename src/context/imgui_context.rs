@@ -194,7 +194,7 @@ impl<'uic> UpdatedImguiContext<'uic>{
         imgui.renderer.render(ui.render(), &winit_context.queue, &winit_context.device, &mut rpass.render_pass)
             .expect("Rendering Failed");
 
-        drop(rpass);
+        rpass.end();
     }
 }
 
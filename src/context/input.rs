@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use winit::event::*;
+
+///
+/// Input state accumulated from the window events of the event loop.
+///
+/// Per frame state such as pressed keys and mouse deltas is reset at the end of every frame
+/// rendered with UpdatedWinitContext::encode.
+///
+#[derive(Default)]
+pub struct Input{
+    keys_held: HashSet<VirtualKeyCode>,
+    keys_pressed: HashSet<VirtualKeyCode>,
+    keys_released: HashSet<VirtualKeyCode>,
+    buttons_held: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    mouse_position: Option<[f32; 2]>,
+    mouse_delta: [f32; 2],
+    scroll: [f32; 2],
+}
+
+impl Input{
+    pub fn new() -> Self{
+        Self::default()
+    }
+
+    pub fn handle_event(&mut self, event: &WindowEvent){
+        match event{
+            WindowEvent::KeyboardInput{
+                input: KeyboardInput{
+                    virtual_keycode: Some(key),
+                    state,
+                    ..
+                },
+                ..
+            } => {
+                match state{
+                    ElementState::Pressed => {
+                        // Key repeats should not count as new presses.
+                        if self.keys_held.insert(*key){
+                            self.keys_pressed.insert(*key);
+                        }
+                    },
+                    ElementState::Released => {
+                        self.keys_held.remove(key);
+                        self.keys_released.insert(*key);
+                    },
+                }
+            },
+            WindowEvent::MouseInput{state, button, ..} => {
+                match state{
+                    ElementState::Pressed => {
+                        self.buttons_held.insert(*button);
+                        self.buttons_pressed.insert(*button);
+                    },
+                    ElementState::Released => {
+                        self.buttons_held.remove(button);
+                    },
+                }
+            },
+            WindowEvent::CursorMoved{position, ..} => {
+                let position = [position.x as f32, position.y as f32];
+                if let Some(last) = self.mouse_position{
+                    self.mouse_delta[0] += position[0] - last[0];
+                    self.mouse_delta[1] += position[1] - last[1];
+                }
+                self.mouse_position = Some(position);
+            },
+            WindowEvent::CursorLeft{..} => {
+                self.mouse_position = None;
+            },
+            WindowEvent::MouseWheel{delta, ..} => {
+                let [x, y] = match delta{
+                    MouseScrollDelta::LineDelta(x, y) => [*x, *y],
+                    MouseScrollDelta::PixelDelta(position) => [position.x as f32, position.y as f32],
+                };
+                self.scroll[0] += x;
+                self.scroll[1] += y;
+            },
+            WindowEvent::Focused(false) => {
+                self.keys_held.clear();
+                self.buttons_held.clear();
+            },
+            _ => {},
+        }
+    }
+
+    ///
+    /// Resets the per frame state.
+    ///
+    pub fn end_frame(&mut self){
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.mouse_delta = [0., 0.];
+        self.scroll = [0., 0.];
+    }
+
+    #[inline]
+    pub fn key_held(&self, key: VirtualKeyCode) -> bool{
+        self.keys_held.contains(&key)
+    }
+
+    ///
+    /// Returns true if the key has been pressed during the current frame.
+    ///
+    #[inline]
+    pub fn key_pressed_this_frame(&self, key: VirtualKeyCode) -> bool{
+        self.keys_pressed.contains(&key)
+    }
+
+    #[inline]
+    pub fn key_released_this_frame(&self, key: VirtualKeyCode) -> bool{
+        self.keys_released.contains(&key)
+    }
+
+    #[inline]
+    pub fn mouse_held(&self, button: MouseButton) -> bool{
+        self.buttons_held.contains(&button)
+    }
+
+    #[inline]
+    pub fn mouse_pressed_this_frame(&self, button: MouseButton) -> bool{
+        self.buttons_pressed.contains(&button)
+    }
+
+    ///
+    /// Returns the position of the cursor in physical pixels or None if it is outside of the
+    /// window.
+    ///
+    #[inline]
+    pub fn mouse_position(&self) -> Option<[f32; 2]>{
+        self.mouse_position
+    }
+
+    ///
+    /// Returns the movement of the cursor during the current frame in physical pixels.
+    ///
+    #[inline]
+    pub fn mouse_delta(&self) -> [f32; 2]{
+        self.mouse_delta
+    }
+
+    ///
+    /// Returns the scrolled distance during the current frame in lines or pixels depending on
+    /// the device.
+    ///
+    #[inline]
+    pub fn scroll(&self) -> [f32; 2]{
+        self.scroll
+    }
+}
@@ -1,6 +1,8 @@
 
 pub mod gpu_context;
 pub mod winit_context;
+pub mod input;
+pub mod framework;
 #[cfg(feature = "imgui")]
 pub mod imgui_context;
 #[cfg(feature = "egui")]
@@ -8,6 +10,8 @@ pub mod egui_context;
 
 pub use gpu_context::*;
 pub use winit_context::*;
+pub use input::*;
+pub use framework::*;
 #[cfg(feature = "imgui")]
 pub use imgui_context::*;
 #[cfg(feature = "egui")]
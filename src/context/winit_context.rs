@@ -6,6 +6,8 @@ use winit::{
     window::Window,
 };
 
+use anyhow::{Result, ensure};
+
 use std::ops::Deref;
 use std::ops::DerefMut;
 
@@ -47,7 +49,7 @@ impl<'wcb> WinitContextBuilder<'wcb>{
 
         let config = wgpu::SurfaceConfiguration{
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_preferred_format(&gpu_context.adapter).unwrap(),
+            format: texture::surface_preferred_format(&gpu_context.adapter, &surface),
             width: size.width,
             height: size.height,
             present_mode: wgpu::PresentMode::Fifo,
@@ -60,6 +62,9 @@ impl<'wcb> WinitContextBuilder<'wcb>{
             config,
             size,
             window,
+            last_frame: None,
+            depth: None,
+            input: Input::new(),
         }
     }
 }
@@ -70,6 +75,9 @@ pub struct WinitContext{
     pub config: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     pub window: Window,
+    last_frame: Option<Texture>,
+    depth: Option<DepthTexture>,
+    pub input: Input,
 }
 
 impl WinitContext{
@@ -84,7 +92,7 @@ impl WinitContext{
 
         let config = wgpu::SurfaceConfiguration{
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_preferred_format(&gpu_context.adapter).unwrap(),
+            format: texture::surface_preferred_format(&gpu_context.adapter, &surface),
             width: size.width,
             height: size.height,
             present_mode: wgpu::PresentMode::Fifo,
@@ -97,6 +105,9 @@ impl WinitContext{
             config,
             size,
             window,
+            last_frame: None,
+            depth: None,
+            input: Input::new(),
         }
     }
     pub(crate) fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>){
@@ -105,11 +116,88 @@ impl WinitContext{
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
+            if self.last_frame.is_some(){
+                self.last_frame = Some(self.create_last_frame());
+            }
+            if let Some(format) = self.depth.as_ref().map(|depth| depth.format){
+                self.depth = Some(self.create_depth(format));
+            }
         }
     }
 
+    fn create_depth(&self, format: wgpu::TextureFormat) -> DepthTexture{
+        DepthTexture::new(TextureBuilder::new()
+            .clear([self.config.width, self.config.height])
+            .depth(format)
+            .label(Some("surface_depth"))
+            .build_empty(&self.device))
+    }
+
+    ///
+    /// Enables a depth texture of format matching the size of the surface or disables it if
+    /// format is None.
+    ///
+    /// The depth texture is recreated whenever the surface is resized, so views of it should not
+    /// be kept across frames.
+    ///
+    pub fn set_depth_format(&mut self, format: Option<wgpu::TextureFormat>){
+        self.depth = format.map(|format| self.create_depth(format));
+    }
+
+    ///
+    /// Returns the view of the depth texture if it is enabled (see set_depth_format).
+    ///
+    pub fn depth_view(&self) -> Option<&wgpu::TextureView>{
+        self.depth.as_ref().map(|depth| &depth.view)
+    }
+
+    pub fn depth_texture(&self) -> Option<&DepthTexture>{
+        self.depth.as_ref()
+    }
+
+    fn create_last_frame(&self) -> Texture{
+        TextureBuilder::new()
+            .clear([self.config.width, self.config.height])
+            .format(self.config.format)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .label(Some("last_frame"))
+            .build_empty(&self.device)
+    }
+
+    ///
+    /// Enables or disables capturing of the presented frames.
+    ///
+    /// If enabled every frame rendered with encode is copied to a Texture before it is presented.
+    /// This requires the surface to be reconfigured with the COPY_SRC usage, which the GL backend
+    /// does not support. Enabling it on such an adapter returns an error and leaves the surface
+    /// untouched.
+    ///
+    pub fn set_frame_capture(&mut self, enabled: bool) -> Result<()>{
+        if enabled{
+            let backend = self.adapter.get_info().backend;
+            ensure!(backend != wgpu::Backend::Gl, "Frame capture is not supported by the {:?} backend", backend);
+            self.config.usage |= wgpu::TextureUsages::COPY_SRC;
+            self.surface.configure(&self.device, &self.config);
+            self.last_frame = Some(self.create_last_frame());
+        }
+        else{
+            self.config.usage &= !wgpu::TextureUsages::COPY_SRC;
+            self.surface.configure(&self.device, &self.config);
+            self.last_frame = None;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the last presented frame if frame capture is enabled.
+    ///
+    pub fn capture_last_frame(&self) -> Option<&Texture>{
+        self.last_frame.as_ref()
+    }
+
     fn update(&mut self) {
         self.gpu_context.update();
+        self.input.end_frame();
     }
 
 
@@ -151,6 +239,7 @@ impl WinitContext{
                 ref event,
                 window_id,
             } if window_id == self.window.id() => {
+                self.input.handle_event(event);
                 match event{
                     WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
                     WindowEvent::Resized(physical_size) => {
@@ -257,7 +346,7 @@ impl<'uwc> UpdatedWinitContext<'uwc>{
         };
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("imgui_encoder")});
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("winit_encoder")});
 
         // Call render function 
         let size = self.size;
@@ -271,7 +360,25 @@ impl<'uwc> UpdatedWinitContext<'uwc>{
             Err(e) => eprintln!("{:?}", e),
         }
 
-        self.queue.submit(Some(encoder.finish()));
+        if let Some(last_frame) = &self.last_frame{
+            encoder.copy_texture_to_texture(
+                wgpu::ImageCopyTexture{
+                    texture: &output.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyTexture{
+                    texture: &last_frame.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                last_frame.size,
+            );
+        }
+
+        self.submit_timed(encoder);
         output.present();
         self.update();
     }
@@ -162,11 +162,16 @@ pub mod push_constants;
 pub mod shader;
 pub mod context;
 pub mod utils;
+pub mod material;
+pub mod timer;
+pub mod sampler;
+pub mod reflect;
+pub mod mesh;
 
 
 pub use self::binding::*;
 pub use self::buffer::*;
-//pub use self::mesh::*;
+pub use self::mesh::*;
 pub use self::pipeline::*;
 pub use self::render_target::*;
 pub use self::texture::*;
@@ -174,6 +179,10 @@ pub use self::uniform::*;
 pub use self::vert::*;
 pub use self::push_constants::*;
 pub use self::shader::*;
+pub use self::material::*;
+pub use self::timer::*;
+pub use self::sampler::*;
+pub use self::reflect::*;
 pub use crate::ewgpu_macros::*;
 pub use context::*;
 
@@ -0,0 +1,74 @@
+use crate::*;
+
+///
+/// The content of a material BindGroup consisting of a uniform with the factors of the material,
+/// N textures and one sampler shared by all textures.
+///
+/// The bindings are laid out as follows:
+/// - 0: the uniform
+/// - 1..=N: the textures in the order they were given
+/// - N + 1: the sampler
+///
+pub struct Material<C: bytemuck::Pod, const N: usize>{
+    pub uniform: Uniform<C>,
+    pub sampler: wgpu::Sampler,
+    textures: [Texture; N],
+    views: Vec<wgpu::TextureView>,
+}
+
+impl<C: bytemuck::Pod, const N: usize> Material<C, N>{
+    pub fn new(device: &wgpu::Device, factors: C, textures: [Texture; N], sampler: wgpu::Sampler) -> Self{
+        let views = textures.iter()
+            .map(|texture| texture.texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            .collect();
+
+        Self{
+            uniform: Uniform::new(factors, device),
+            sampler,
+            textures,
+            views,
+        }
+    }
+
+    #[inline]
+    pub fn textures(&self) -> &[Texture; N]{
+        &self.textures
+    }
+
+    #[inline]
+    pub const fn uniform_binding() -> u32{
+        0
+    }
+
+    #[inline]
+    pub const fn texture_binding(index: usize) -> u32{
+        1 + index as u32
+    }
+
+    #[inline]
+    pub const fn sampler_binding() -> u32{
+        1 + N as u32
+    }
+}
+
+impl<C: bytemuck::Pod, const N: usize> BindGroupContent for Material<C, N>{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        let visibility = visibility.unwrap_or(wgpu::ShaderStages::all());
+        let mut ret = Vec::with_capacity(N + 2);
+        ret.push(BindGroupLayoutEntry::new(visibility, binding::wgsl::uniform()));
+        for _i in 0..N{
+            ret.push(BindGroupLayoutEntry::new(visibility, binding::wgsl::texture_2d()));
+        }
+        ret.push(BindGroupLayoutEntry::new(visibility, binding::wgsl::sampler()));
+        ret
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource> {
+        let mut ret = self.uniform.resources();
+        for view in &self.views{
+            ret.push(wgpu::BindingResource::TextureView(view));
+        }
+        ret.push(wgpu::BindingResource::Sampler(&self.sampler));
+        ret
+    }
+}
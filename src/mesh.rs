@@ -0,0 +1,40 @@
+use crate::*;
+use std::ops::Range;
+
+///
+/// A trait implemented by types that own their geometry and can record their draw calls into a
+/// render pass with a pipeline already set.
+///
+pub trait Drawable{
+    fn draw<'rp>(&'rp self, pass: &mut RenderPassPipeline<'rp, '_>){
+        self.draw_instanced(pass, 0..1);
+    }
+    fn draw_instanced<'rp>(&'rp self, pass: &mut RenderPassPipeline<'rp, '_>, instances: Range<u32>);
+}
+
+///
+/// Indexed geometry with a vertex buffer bound to slot 0.
+///
+pub struct Mesh<V: VertLayout, I: bytemuck::Pod> where Buffer<I>: IndexFormat{
+    pub vertices: Buffer<V>,
+    pub indices: IndexBuffer<I>,
+}
+
+impl<V: VertLayout, I: bytemuck::Pod> Mesh<V, I> where Buffer<I>: IndexFormat{
+    pub fn new(device: &wgpu::Device, vertices: &[V], indices: &[I], label: wgpu::Label) -> Self{
+        Self{
+            vertices: BufferBuilder::new()
+                .vertex()
+                .set_label(label)
+                .build(device, vertices),
+            indices: IndexBuffer::new(device, label, indices),
+        }
+    }
+}
+
+impl<V: VertLayout, I: bytemuck::Pod> Drawable for Mesh<V, I> where Buffer<I>: IndexFormat{
+    fn draw_instanced<'rp>(&'rp self, pass: &mut RenderPassPipeline<'rp, '_>, instances: Range<u32>){
+        pass.set_vertex_buffer(0, self.vertices.slice(..));
+        self.indices.draw_indexed_all(pass, 0, instances);
+    }
+}
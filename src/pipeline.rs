@@ -1,13 +1,27 @@
 use std::str;
 use crate::*;
+use anyhow::{Result, ensure};
 
 use core::ops::Range;
 use core::num::NonZeroU32;
+use std::marker::PhantomData;
+use std::cell::Cell;
 
-#[allow(unused)]
-const DEFAULT_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8Unorm;
 pub const DEFAULT_ENTRY_POINT: &str = "main";
 
+pub const ADDITIVE_BLEND_STATE: wgpu::BlendState = wgpu::BlendState{
+    color: wgpu::BlendComponent{
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent{
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+};
+
 ///
 /// A struct representing a FragmentState.
 ///
@@ -36,6 +50,13 @@ impl<'fs> FragmentState<'fs>{
         self
     }
 
+    ///
+    /// Pushes a target with the default format (see texture::default_format).
+    ///
+    pub fn push_target_default(self) -> Self{
+        self.push_target_replace(texture::default_format())
+    }
+
     pub fn push_target_replace(mut self, format: wgpu::TextureFormat) -> Self{
         self.targets.push(wgpu::ColorTargetState{
             format,
@@ -47,6 +68,108 @@ impl<'fs> FragmentState<'fs>{
         });
         self
     }
+
+    pub fn push_target_blend(mut self, format: wgpu::TextureFormat, blend: wgpu::BlendState) -> Self{
+        self.targets.push(wgpu::ColorTargetState{
+            format,
+            blend: Some(blend),
+            write_mask: wgpu::ColorWrites::all(),
+        });
+        self
+    }
+
+    ///
+    /// Pushes a target blending the color with the target according to its alpha value.
+    ///
+    pub fn push_target_alpha_blend(self, format: wgpu::TextureFormat) -> Self{
+        self.push_target_blend(format, wgpu::BlendState::ALPHA_BLENDING)
+    }
+
+    ///
+    /// Pushes a target adding the color weighted by its alpha value to the target.
+    ///
+    pub fn push_target_additive(self, format: wgpu::TextureFormat) -> Self{
+        self.push_target_blend(format, ADDITIVE_BLEND_STATE)
+    }
+
+    ///
+    /// Pushes a target blending colors that have already been multiplied by their alpha value.
+    ///
+    pub fn push_target_premultiplied(self, format: wgpu::TextureFormat) -> Self{
+        self.push_target_blend(format, wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING)
+    }
+}
+
+///
+/// A vertex buffer layout owning its attributes so that it can be computed at runtime.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexBufferLayout{
+    pub array_stride: wgpu::BufferAddress,
+    pub step_mode: wgpu::VertexStepMode,
+    pub attributes: Vec<wgpu::VertexAttribute>,
+}
+
+impl VertexBufferLayout{
+    ///
+    /// Creates a layout of tightly packed attributes at sequential shader locations starting at
+    /// first_location.
+    ///
+    /// Every offset is aligned to the size of its attribute, capped at 4 bytes.
+    /// The stride is aligned to wgpu::VERTEX_STRIDE_ALIGNMENT.
+    /// This is not always the layout of a #[repr(C)] struct with the same fields.
+    /// For example, a Float64 after a Float32 is placed at offset 4 here and at offset 8 in the struct.
+    /// Use push_vert_layout with the struct's own layout (see VertLayout) for vertex structs.
+    ///
+    pub fn interleaved(step_mode: wgpu::VertexStepMode, attributes: &[wgpu::VertexFormat], first_location: u32) -> Self{
+        let align = |offset: u64, alignment: u64| (offset + alignment - 1) / alignment * alignment;
+
+        let mut offset = 0;
+        let attributes = attributes.iter().enumerate()
+            .map(|(i, format)|{
+                offset = align(offset, format.size().min(4));
+                let attribute = wgpu::VertexAttribute{
+                    format: *format,
+                    offset,
+                    shader_location: first_location + i as u32,
+                };
+                offset += format.size();
+                attribute
+            }).collect();
+
+        Self{
+            array_stride: align(offset, wgpu::VERTEX_STRIDE_ALIGNMENT),
+            step_mode,
+            attributes,
+        }
+    }
+
+    ///
+    /// The shader location following the last attribute of the layout.
+    ///
+    pub fn next_location(&self) -> u32{
+        self.attributes.iter()
+            .map(|attribute| attribute.shader_location + 1)
+            .max().unwrap_or(0)
+    }
+
+    pub fn as_wgpu(&self) -> wgpu::VertexBufferLayout{
+        wgpu::VertexBufferLayout{
+            array_stride: self.array_stride,
+            step_mode: self.step_mode,
+            attributes: &self.attributes,
+        }
+    }
+}
+
+impl<'vbl> From<wgpu::VertexBufferLayout<'vbl>> for VertexBufferLayout{
+    fn from(layout: wgpu::VertexBufferLayout<'vbl>) -> Self{
+        Self{
+            array_stride: layout.array_stride,
+            step_mode: layout.step_mode,
+            attributes: layout.attributes.to_vec(),
+        }
+    }
 }
 
 ///
@@ -55,7 +178,8 @@ impl<'fs> FragmentState<'fs>{
 /// RenderPassPipeline process.
 ///
 pub struct VertexState<'vs>{
-    pub vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'vs>>,
+    /// Owned layouts, layouts pushed as wgpu::VertexBufferLayout are converted.
+    pub vertex_buffer_layouts: Vec<VertexBufferLayout>,
     pub entry_point: &'vs str,
     pub shader: &'vs wgpu::ShaderModule,
 }
@@ -72,12 +196,31 @@ impl<'vs> VertexState<'vs>{
         self.entry_point = entry_point;
         self
     }
-    pub fn push_vert_layout(mut self, vertex_buffer_layout: wgpu::VertexBufferLayout<'vs>) -> Self{
-        self.vertex_buffer_layouts.push(vertex_buffer_layout);
+    pub fn push_vert_layout(mut self, vertex_buffer_layout: wgpu::VertexBufferLayout) -> Self{
+        self.vertex_buffer_layouts.push(vertex_buffer_layout.into());
         self
     }
-    pub fn push_vert_layouts(mut self, mut vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'vs>>) -> Self{
-        self.vertex_buffer_layouts.append(&mut vertex_buffer_layouts);
+    pub fn push_vert_layouts(mut self, vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout>) -> Self{
+        self.vertex_buffer_layouts.extend(vertex_buffer_layouts.into_iter().map(VertexBufferLayout::from));
+        self
+    }
+    ///
+    /// Pushes a buffer layout that is stepped per instance instead of per vertex.
+    ///
+    pub fn push_instance_layout(self, mut instance_buffer_layout: wgpu::VertexBufferLayout) -> Self{
+        instance_buffer_layout.step_mode = wgpu::VertexStepMode::Instance;
+        self.push_vert_layout(instance_buffer_layout)
+    }
+    ///
+    /// Pushes a buffer layout of tightly packed attributes (see VertexBufferLayout::interleaved).
+    ///
+    /// The shader locations continue after the highest location of the previous layouts.
+    ///
+    pub fn push_interleaved(mut self, step_mode: wgpu::VertexStepMode, attributes: &[wgpu::VertexFormat]) -> Self{
+        let first_location = self.vertex_buffer_layouts.iter()
+            .map(|layout| layout.next_location())
+            .max().unwrap_or(0);
+        self.vertex_buffer_layouts.push(VertexBufferLayout::interleaved(step_mode, attributes, first_location));
         self
     }
 }
@@ -89,12 +232,19 @@ impl<'vs> VertexState<'vs>{
 pub struct RenderPipeline{
     #[target]
     pub pipeline: wgpu::RenderPipeline,
-    pub push_const_ranges: Vec<wgpu::PushConstantRange>
+    pub push_const_ranges: Vec<wgpu::PushConstantRange>,
+    /// Number of bind groups in the layout, None if the layout was derived from the shader.
+    pub bind_group_count: Option<u32>,
+    /// Step modes of the vertex buffer slots.
+    pub step_modes: Vec<wgpu::VertexStepMode>,
+    /// Number of layers rendered by a multiview pipeline.
+    pub multiview: Option<NonZeroU32>,
 }
 
 pub struct PipelineLayout{
     pub layout: wgpu::PipelineLayout,
     pub push_const_ranges: Vec<wgpu::PushConstantRange>,
+    pub bind_group_count: u32,
 }
 
 impl PipelineLayout{
@@ -105,22 +255,7 @@ impl PipelineLayout{
     ///
     pub fn new(device: &wgpu::Device, bind_group_layouts: &[&wgpu::BindGroupLayout], push_const_layouts: &[PushConstantLayout], label: wgpu::Label) -> Self{
 
-        let mut offset = 0;
-        let push_const_ranges: Vec<wgpu::PushConstantRange> = push_const_layouts.iter()
-            .map(|x| {
-                // align to 4 bytes.
-                // TODO: write tests and use Align trait.
-                let size_aligned = (((x.size as i32 - 4)/4 + 1)*4) as u32;
-                let range = Range::<u32>{
-                    start: offset,
-                    end: offset + size_aligned,
-                };
-                offset = range.end;
-                wgpu::PushConstantRange{
-                    stages: x.stages,
-                    range,
-                }
-            }).collect();
+        let push_const_ranges = push_const_ranges(push_const_layouts);
 
         Self{
             layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor{
@@ -129,6 +264,7 @@ impl PipelineLayout{
                 bind_group_layouts,
             }),
             push_const_ranges,
+            bind_group_count: bind_group_layouts.len() as u32,
         }
     }
 }
@@ -166,22 +302,7 @@ impl<'l> PipelineLayoutBuilder<'l>{
             bind_group_layouts.push(&bind_group_layout_desc.layout);
         }
 
-        // Convert the push_const_layouts to push_const_ranges using alignment
-        let mut offset = 0;
-        let push_const_ranges: Vec<wgpu::PushConstantRange> = self.push_const_layouts.iter()
-            .map(|x| {
-                // align to 4 bytes.
-                let size_aligned = (((x.size as i32 - 4)/4 + 1)*4) as u32;
-                let range = Range::<u32>{
-                    start: offset,
-                    end: offset + size_aligned,
-                };
-                offset = range.end;
-                wgpu::PushConstantRange{
-                    stages: x.stages,
-                    range,
-                }
-            }).collect();
+        let push_const_ranges = push_const_ranges(&self.push_const_layouts);
 
         PipelineLayout{
             layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor{
@@ -190,6 +311,7 @@ impl<'l> PipelineLayoutBuilder<'l>{
                 push_constant_ranges: &push_const_ranges,
             }),
             push_const_ranges,
+            bind_group_count: bind_group_layouts.len() as u32,
         }
     }
 }
@@ -201,6 +323,7 @@ pub struct RenderPassPipeline<'rp, 'rpr>{
 
 impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
     pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'rp B, offsets: &'rp [wgpu::DynamicOffset]){
+        debug_assert_bind_group_index(index, self.pipeline.bind_group_count);
         self.render_pass.render_pass.set_bind_group(
             index,
             bind_group.bind_group(),
@@ -210,6 +333,7 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
 
     pub fn set_bind_groups(&mut self, bind_groups: &[&'rp wgpu::BindGroup]){
         for (i, bind_group) in bind_groups.iter().enumerate(){
+            debug_assert_bind_group_index(i as u32, self.pipeline.bind_group_count);
             self.render_pass.render_pass.set_bind_group(
                 i as u32,
                 &bind_group,
@@ -218,14 +342,46 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         }
     }
 
+    ///
+    /// Sets the push constant at index.
+    ///
+    /// Panics if there is no push constant range at index (see push_const_range).
+    ///
     pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C){
+        let range = push_const_range::<C>(&self.pipeline.push_const_ranges, index);
         self.render_pass.render_pass.set_push_constants(
-            self.pipeline.push_const_ranges[index].stages, 
-            self.pipeline.push_const_ranges[index].range.start,
+            range.stages, 
+            range.range.start,
             bytemuck::bytes_of(constant));
     }
 
+    ///
+    /// In debug builds this asserts that the step mode of T matches the step mode of the slot in
+    /// the pipeline.
+    ///
     pub fn set_vertex_buffer<T: VertLayout>(&mut self, index: u32, buffer_slice: BufferSlice<'rp, T>){
+        if let Some(step_mode) = self.pipeline.step_modes.get(index as usize){
+            debug_assert_eq!(
+                T::buffer_layout().step_mode, *step_mode,
+                "Step mode of the buffer does not match the step mode of vertex buffer slot {}", index
+            );
+        }
+        self.render_pass.render_pass.set_vertex_buffer(
+            index,
+            buffer_slice.into()
+        );
+    }
+
+    ///
+    /// Sets a buffer that is stepped per instance.
+    ///
+    /// In debug builds this asserts that slot index of the pipeline has the Instance step mode.
+    ///
+    pub fn set_instance_buffer<T: VertLayout>(&mut self, index: u32, buffer_slice: BufferSlice<'rp, T>){
+        debug_assert_eq!(
+            self.pipeline.step_modes.get(index as usize), Some(&wgpu::VertexStepMode::Instance),
+            "Vertex buffer slot {} is not an instance slot", index
+        );
         self.render_pass.render_pass.set_vertex_buffer(
             index,
             buffer_slice.into()
@@ -240,7 +396,47 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         self.render_pass.render_pass.set_index_buffer(buffer_slice.into(), wgpu::IndexFormat::Uint16);
     }
 
+    ///
+    /// Sets the color used by BlendFactor::Constant and BlendFactor::OneMinusConstant.
+    ///
+    #[inline]
+    pub fn set_blend_constant(&mut self, color: wgpu::Color){
+        self.render_pass.render_pass.set_blend_constant(color);
+    }
+
+    ///
+    /// Sets the value stencil operations compare against and write with StencilOperation::Replace.
+    ///
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: u32){
+        self.render_pass.render_pass.set_stencil_reference(reference);
+    }
+
+    ///
+    /// Starts a debug group with label that is shown in graphics debuggers such as RenderDoc.
+    /// Has to be closed with pop_debug_group.
+    ///
+    #[inline]
+    pub fn push_debug_group(&mut self, label: &str){
+        self.render_pass.render_pass.push_debug_group(label);
+    }
+
+    #[inline]
+    pub fn pop_debug_group(&mut self){
+        self.render_pass.render_pass.pop_debug_group();
+    }
+
+    ///
+    /// Inserts a single labeled marker that is shown in graphics debuggers.
+    ///
+    #[inline]
+    pub fn insert_debug_marker(&mut self, label: &str){
+        self.render_pass.render_pass.insert_debug_marker(label);
+    }
+
     pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>){
+        #[cfg(feature = "render_stats")]
+        self.render_pass.stats.count_draw(vertices.end - vertices.start, instances.end - instances.start);
         self.render_pass.render_pass.draw(
             vertices.start..vertices.end,
             instances.start..instances.end
@@ -248,6 +444,8 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
     }
 
     pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>){
+        #[cfg(feature = "render_stats")]
+        self.render_pass.stats.count_draw(indices.end - indices.start, instances.end - instances.start);
         self.render_pass.render_pass.draw_indexed(
             indices.start..indices.end, 
             base_vertex, 
@@ -255,6 +453,65 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         );
     }
 
+    ///
+    /// Draws vertex_count vertices of one instance without requiring a vertex buffer.
+    ///
+    /// Used for vertex pulling where the vertex shader reads its data from a storage buffer
+    /// bound in a bind group using gl_VertexIndex, or generates the vertices itself as for a
+    /// fullscreen triangle. The pipeline is built without pushing any vertex layouts.
+    ///
+    /// ```ignore
+    /// let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+    ///     .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+    ///     .set_layout(&layout)
+    ///     .build(&gpu.device);
+    ///
+    /// let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+    /// rpass_ppl.set_bind_group(0, &vertices, &[]);
+    /// rpass_ppl.draw_procedural(vertices.len() as u32);
+    /// ```
+    ///
+    #[inline]
+    pub fn draw_procedural(&mut self, vertex_count: u32){
+        self.draw(0..vertex_count, 0..1);
+    }
+
+    pub fn draw_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndirect>, indirect_offset: wgpu::BufferAddress){
+        debug_assert_indirect::<DrawIndirect>(indirect_buffer.size(), indirect_offset);
+        self.render_pass.render_pass.draw_indirect(&indirect_buffer.buffer, indirect_offset);
+    }
+
+    pub fn draw_indexed_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndexedIndirect>, indirect_offset: wgpu::BufferAddress){
+        debug_assert_indirect::<DrawIndexedIndirect>(indirect_buffer.size(), indirect_offset);
+        self.render_pass.render_pass.draw_indexed_indirect(&indirect_buffer.buffer, indirect_offset);
+    }
+
+    ///
+    /// Issues count draw calls from consecutive DrawIndirect structs in the buffer.
+    ///
+    /// Requires the wgpu::Features::MULTI_DRAW_INDIRECT feature.
+    ///
+    pub fn multi_draw_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndirect>, indirect_offset: wgpu::BufferAddress, count: u32){
+        debug_assert_indirect::<DrawIndirect>(
+            indirect_buffer.size(),
+            indirect_offset + (count.max(1) as u64 - 1) * std::mem::size_of::<DrawIndirect>() as u64
+        );
+        self.render_pass.render_pass.multi_draw_indirect(&indirect_buffer.buffer, indirect_offset, count);
+    }
+
+    ///
+    /// Issues count indexed draw calls from consecutive DrawIndexedIndirect structs in the buffer.
+    ///
+    /// Requires the wgpu::Features::MULTI_DRAW_INDIRECT feature.
+    ///
+    pub fn multi_draw_indexed_indirect(&mut self, indirect_buffer: &'rp Buffer<DrawIndexedIndirect>, indirect_offset: wgpu::BufferAddress, count: u32){
+        debug_assert_indirect::<DrawIndexedIndirect>(
+            indirect_buffer.size(),
+            indirect_offset + (count.max(1) as u64 - 1) * std::mem::size_of::<DrawIndexedIndirect>() as u64
+        );
+        self.render_pass.render_pass.multi_draw_indexed_indirect(&indirect_buffer.buffer, indirect_offset, count);
+    }
+
     pub fn set_viewport(&mut self, x: Range<f32>, y: Range<f32>, depth: Range<f32>){
         self.render_pass.render_pass.set_viewport(
             x.start, y.start,
@@ -263,6 +520,13 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
         );
     }
 
+    ///
+    /// Sets the scissor rectangle, fragments outside of it are discarded.
+    ///
+    pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32){
+        self.render_pass.render_pass.set_scissor_rect(x, y, width, height);
+    }
+
     pub fn set_pipeline(&'rpr mut self, pipeline: &'rp RenderPipeline) -> Self{
         self.render_pass.render_pass.set_pipeline(&pipeline.pipeline);
         Self{
@@ -272,6 +536,57 @@ impl<'rp, 'rpr> RenderPassPipeline<'rp, 'rpr>{
     }
 }
 
+///
+/// Checks in debug builds that a bind group index is inside the layout of the pipeline.
+///
+#[inline]
+pub(crate) fn debug_assert_bind_group_index(index: u32, bind_group_count: Option<u32>){
+    if let Some(bind_group_count) = bind_group_count{
+        debug_assert_lt!(
+            index, bind_group_count,
+            "Bind group index {} is out of range, the pipeline layout has {} bind groups", index, bind_group_count
+        );
+    }
+}
+
+///
+/// Checks in debug builds that an indirect argument of type T at offset is 4 byte aligned and fits
+/// into a buffer of buffer_size bytes.
+///
+#[inline]
+pub(crate) fn debug_assert_indirect<T>(buffer_size: usize, offset: wgpu::BufferAddress){
+    debug_assert_eq!(offset % 4, 0, "Indirect offset {} is not 4 byte aligned", offset);
+    debug_assert_le!(
+        offset as usize + std::mem::size_of::<T>(), buffer_size,
+        "Indirect arguments at offset {} exceed the buffer of {} bytes", offset, buffer_size
+    );
+}
+
+///
+/// Arguments of draw_indirect as expected by wgpu.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndirect{
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub base_vertex: u32,
+    pub base_instance: u32,
+}
+
+///
+/// Arguments of draw_indexed_indirect as expected by wgpu.
+///
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndexedIndirect{
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub base_index: u32,
+    pub vertex_offset: i32,
+    pub base_instance: u32,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct DispatchIndirect{
@@ -283,9 +598,12 @@ pub struct DispatchIndirect{
 ///
 /// Wrapper for wgpu::ComputePass
 ///
+#[must_use = "a ComputePass records nothing unless commands are set on it"]
 #[derive(DerefMut)]
 pub struct ComputePass<'cp>{
+    #[target]
     pub cpass: wgpu::ComputePass<'cp>,
+    timestamps: Option<&'cp TimestampQuery>,
 }
 
 impl<'cp> ComputePass<'cp>{
@@ -295,9 +613,23 @@ impl<'cp> ComputePass<'cp>{
         });
         Self{
             cpass,
+            timestamps: None,
         }
     }
 
+    ///
+    /// Begins a compute pass that writes timestamps to timestamps at its start and when it is
+    /// ended or dropped.
+    ///
+    /// Requires the TIMESTAMP_QUERY feature.
+    ///
+    pub fn new_timed(encoder: &'cp mut wgpu::CommandEncoder, timestamps: &'cp TimestampQuery, label: Option<&str>) -> Self{
+        let mut cpass = Self::new(encoder, label);
+        cpass.cpass.write_timestamp(timestamps.query_set(), 0);
+        cpass.timestamps = Some(timestamps);
+        cpass
+    }
+
     pub fn set_pipeline(&mut self, pipeline: &'cp ComputePipeline) -> ComputePassPipeline<'cp, '_>{
         self.cpass.set_pipeline(&pipeline.pipeline);
         ComputePassPipeline{
@@ -306,6 +638,38 @@ impl<'cp> ComputePass<'cp>{
         }
     }
 
+    ///
+    /// Ends the pass.
+    ///
+    /// The encoder the pass was begun on is borrowed until the pass is ended, so this has to be
+    /// called (or the pass dropped) before the encoder can be used again.
+    ///
+    #[inline]
+    pub fn end(self){
+        drop(self);
+    }
+}
+
+impl<'cp> Drop for ComputePass<'cp>{
+    fn drop(&mut self){
+        if let Some(timestamps) = self.timestamps{
+            self.cpass.write_timestamp(timestamps.query_set(), 1);
+        }
+    }
+}
+
+///
+/// Returns the number of workgroups of local_size needed to cover total invocations,
+/// rounding up on every axis.
+///
+pub fn workgroup_count(total: [u32; 3], local_size: [u32; 3]) -> [u32; 3]{
+    debug_assert!(local_size.iter().all(|size| *size > 0), "Workgroup size has to be greater than zero");
+    let count = |total: u32, local: u32| total / local + (total % local != 0) as u32;
+    [
+        count(total[0], local_size[0]),
+        count(total[1], local_size[1]),
+        count(total[2], local_size[2]),
+    ]
 }
 
 ///
@@ -318,20 +682,114 @@ pub struct ComputePassPipeline<'cp, 'cpr>{
 
 impl<'cp, 'cpr> ComputePassPipeline<'cp, 'cpr>{
     pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'cp B, offsets: &'cp [wgpu::DynamicOffset]){
+        debug_assert_bind_group_index(index, self.pipeline.bind_group_count);
         self.cpass.cpass.set_bind_group(index, bind_group.bind_group(), offsets);
     }
 
+    ///
+    /// Sets the push constant at index.
+    /// Compute passes have no stages argument, the range has to be visible to the compute stage
+    /// which is asserted when building the ComputePipeline.
+    ///
     pub fn set_push_const<C: PushConstant>(&mut self, index: u32, constant: &C){
+        let range = &push_const_range::<C>(&self.pipeline.push_const_ranges, index as usize).range;
         self.cpass.cpass.set_push_constants(
-            self.pipeline.push_const_ranges[index as usize].range.start,
+            range.start,
             bytemuck::bytes_of(constant));
     }
 
+    ///
+    /// Sets the parameters of the compute shader either as push constant or through the uniform
+    /// buffer depending on the backing of the ComputeUniforms.
+    ///
+    /// When using the uniform buffer every call writes the value to the next slot of the buffer
+    /// and binds it with a dynamic offset, so every dispatch sees the value set before it.
+    ///
+    pub fn set_params<C: bytemuck::Pod>(&mut self, queue: &wgpu::Queue, params: &'cp ComputeUniforms<C>, value: &C){
+        match params{
+            ComputeUniforms::PushConstant{index, ..} => {
+                self.set_push_const(*index, value);
+            },
+            ComputeUniforms::Uniform{group, buffer, bind_group, ..} => {
+                let offset = params.next_offset();
+                queue.write_buffer(buffer, offset as wgpu::BufferAddress, bytemuck::bytes_of(value));
+                debug_assert_bind_group_index(*group, self.pipeline.bind_group_count);
+                self.cpass.cpass.set_bind_group(*group, bind_group, &[offset]);
+            },
+        }
+    }
+
     pub fn dispatch(&mut self, x: u32, y: u32, z: u32){
         self.cpass.cpass.dispatch(x, y, z);
     }
 
+    ///
+    /// Dispatches enough workgroups of local_size to cover total invocations on every axis.
+    /// The shader has to discard the invocations outside of total.
+    ///
+    pub fn dispatch_for(&mut self, total: [u32; 3], local_size: [u32; 3]){
+        let [x, y, z] = workgroup_count(total, local_size);
+        self.dispatch(x, y, z);
+    }
+
+    ///
+    /// Starts a debug group with label that is shown in graphics debuggers such as RenderDoc.
+    /// Has to be closed with pop_debug_group.
+    ///
+    #[inline]
+    pub fn push_debug_group(&mut self, label: &str){
+        self.cpass.cpass.push_debug_group(label);
+    }
+
+    #[inline]
+    pub fn pop_debug_group(&mut self){
+        self.cpass.cpass.pop_debug_group();
+    }
+
+    ///
+    /// Inserts a single labeled marker that is shown in graphics debuggers.
+    ///
+    #[inline]
+    pub fn insert_debug_marker(&mut self, label: &str){
+        self.cpass.cpass.insert_debug_marker(label);
+    }
+
+    ///
+    /// Dispatches the pipeline and records its duration under name in the timer
+    /// (see GPUContext::timings).
+    /// If the capacity of the timer is exhausted the dispatch is not timed.
+    ///
+    /// Also accepts an Option such as gpu.timer.as_mut(), without a timer this is a plain
+    /// dispatch.
+    ///
+    pub fn timed_dispatch<'t>(&mut self, timer: impl Into<Option<&'t mut ScopedTimer>>, name: &str, x: u32, y: u32, z: u32){
+        match timer.into().and_then(|timer| timer.begin_scope(name).map(|scope| (timer, scope))){
+            Some((timer, (begin, end))) => {
+                self.cpass.cpass.write_timestamp(timer.query_set(), begin);
+                self.dispatch(x, y, z);
+                self.cpass.cpass.write_timestamp(timer.query_set(), end);
+            },
+            None => self.dispatch(x, y, z),
+        }
+    }
+
+    ///
+    /// Dispatches the pipeline once for every tile.
+    /// Before each dispatch the push constant at push_index is set to the tile's constant.
+    ///
+    pub fn dispatch_tiles<C: PushConstant>(&mut self, tiles: impl Iterator<Item = (C, [u32; 3])>, push_index: u32){
+        for (constant, [x, y, z]) in tiles{
+            self.set_push_const(push_index, &constant);
+            self.dispatch(x, y, z);
+        }
+    }
+
+    ///
+    /// In debug builds this asserts that indirect_offset is 4 byte aligned and that the
+    /// DispatchIndirect at indirect_offset lies inside the buffer.
+    ///
     pub fn dispatch_indirect(&mut self, indirect_buffer: &'cp Buffer<DispatchIndirect>, indirect_offset: wgpu::BufferAddress){
+        debug_assert_indirect::<DispatchIndirect>(indirect_buffer.size(), indirect_offset);
         self.cpass.cpass.dispatch_indirect(&indirect_buffer.buffer, indirect_offset);
     }
 
@@ -344,6 +802,122 @@ impl<'cp, 'cpr> ComputePassPipeline<'cp, 'cpr>{
     }
 }
 
+///
+/// Parameters of a compute shader that are passed as push constants if the device supports them
+/// and through a uniform buffer otherwise.
+///
+/// The shader has to declare the parameters according to uses_push_constants,
+/// for example by defining a macro when compiling it.
+///
+/// The uniform buffer is used as a ring of slots, one per call to ComputePassPipeline::set_params.
+/// Slots are reused after capacity calls, so at most capacity values can be set per submission.
+///
+pub enum ComputeUniforms<C: bytemuck::Pod>{
+    PushConstant{
+        index: u32,
+        _ty: PhantomData<C>,
+    },
+    Uniform{
+        group: u32,
+        buffer: wgpu::Buffer,
+        stride: u32,
+        capacity: u32,
+        next: Cell<u32>,
+        bind_group_layout: binding::BindGroupLayoutWithDesc,
+        bind_group: wgpu::BindGroup,
+    },
+}
+
+impl<C: bytemuck::Pod> ComputeUniforms<C>{
+    ///
+    /// Selects the backing based on the features of the device.
+    /// push_index is the index of the push constant range and group the index of the bind group
+    /// used by the uniform buffer.
+    ///
+    pub fn new(device: &wgpu::Device, push_index: u32, group: u32) -> Self{
+        Self::with_capacity(device, push_index, group, 256)
+    }
+
+    ///
+    /// Like new but with capacity slots in the uniform buffer.
+    ///
+    pub fn with_capacity(device: &wgpu::Device, push_index: u32, group: u32, capacity: u32) -> Self{
+        if device.features().contains(wgpu::Features::PUSH_CONSTANTS){
+            Self::PushConstant{
+                index: push_index,
+                _ty: PhantomData,
+            }
+        }
+        else{
+            assert!(capacity > 0, "ComputeUniforms need at least one slot");
+            let size = std::mem::size_of::<C>() as u32;
+            let alignment = device.limits().min_uniform_buffer_offset_alignment;
+            let stride = (size + alignment - 1) / alignment * alignment;
+
+            let buffer = device.create_buffer(&wgpu::BufferDescriptor{
+                label: Some("compute_uniforms"),
+                size: (stride * capacity) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let (bind_group_layout, bind_group) = binding::quick_bind_group(device, &[
+                (
+                    wgpu::ShaderStages::COMPUTE,
+                    wgpu::BindingType::Buffer{
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: wgpu::BufferSize::new(size as u64),
+                    },
+                    wgpu::BindingResource::Buffer(wgpu::BufferBinding{
+                        buffer: &buffer,
+                        offset: 0,
+                        size: wgpu::BufferSize::new(size as u64),
+                    }),
+                ),
+            ]);
+            Self::Uniform{
+                group,
+                buffer,
+                stride,
+                capacity,
+                next: Cell::new(0),
+                bind_group_layout,
+                bind_group,
+            }
+        }
+    }
+
+    ///
+    /// Returns the offset of the next slot in the uniform buffer and advances the ring.
+    ///
+    fn next_offset(&self) -> u32{
+        match self{
+            Self::PushConstant{..} => 0,
+            Self::Uniform{stride, capacity, next, ..} => {
+                let slot = next.get();
+                next.set((slot + 1) % capacity);
+                slot * stride
+            },
+        }
+    }
+
+    #[inline]
+    pub fn uses_push_constants(&self) -> bool{
+        matches!(self, Self::PushConstant{..})
+    }
+
+    ///
+    /// Pushes either the push constant layout or the bind group layout to the builder.
+    /// The bind group has to end up at the group index given in new.
+    ///
+    pub fn push_layout<'l>(&'l self, builder: PipelineLayoutBuilder<'l>) -> PipelineLayoutBuilder<'l>{
+        match self{
+            Self::PushConstant{..} => builder.push_const_layout(C::push_const_layout(wgpu::ShaderStages::COMPUTE)),
+            Self::Uniform{bind_group_layout, ..} => builder.push_bind_group(bind_group_layout),
+        }
+    }
+}
+
 ///
 /// A wrapper for wgpu::ComputePipeline with PushConstantRanges
 ///
@@ -352,6 +926,26 @@ pub struct ComputePipeline{
     #[target]
     pub pipeline: wgpu::ComputePipeline,
     pub push_const_ranges: Vec<wgpu::PushConstantRange>,
+    /// Number of bind groups in the layout, None if the layout was derived from the shader.
+    pub bind_group_count: Option<u32>,
+}
+
+impl ComputePipeline{
+    ///
+    /// Returns the size in bytes of the push constant range at index.
+    ///
+    /// Panics if there is no push constant range at index.
+    ///
+    #[inline]
+    pub fn push_const_size(&self, index: usize) -> u32{
+        let range = &self.push_const_ranges[index].range;
+        range.end - range.start
+    }
+
+    #[inline]
+    pub fn push_const_count(&self) -> usize{
+        self.push_const_ranges.len()
+    }
 }
 
 ///
@@ -372,7 +966,7 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
             label: None,
             layout: None,
             module,
-            entry_point: "main",
+            entry_point: module.entry_point(),
         }
     }
 
@@ -391,17 +985,43 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
         self
     }
 
+    ///
+    /// Builds the ComputePipeline.
+    ///
+    /// If no layout is provided the layout is derived from the shader and the bind group layouts
+    /// can be retrieved with get_bind_group_layout. Such a pipeline has no push constant ranges.
+    ///
+    /// Panics if the builder is invalid (see try_build).
+    ///
     pub fn build(&mut self, device: &wgpu::Device) -> ComputePipeline{
-        let layout = self.layout.expect("no layout provided");
-        ComputePipeline{
+        self.try_build(device).unwrap()
+    }
+
+    ///
+    /// Builds the pipeline or returns an error if the builder is invalid.
+    ///
+    /// All push constant ranges of the layout have to be visible to the compute stage.
+    ///
+    pub fn try_build(&mut self, device: &wgpu::Device) -> Result<ComputePipeline>{
+        if let Some(layout) = self.layout{
+            for (i, range) in layout.push_const_ranges.iter().enumerate(){
+                ensure!(
+                    range.stages.contains(wgpu::ShaderStages::COMPUTE),
+                    "push constant range {} ({:?}) is not visible to the compute stage",
+                    i, range.stages
+                );
+            }
+        }
+        Ok(ComputePipeline{
             pipeline: device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor{
                 label: self.label,
-                layout: Some(&layout.layout),
+                layout: self.layout.map(|layout| &layout.layout),
                 module: self.module,
                 entry_point: self.entry_point,
             }),
-            push_const_ranges: layout.push_const_ranges.clone(),
-        }
+            push_const_ranges: self.layout.map(|layout| layout.push_const_ranges.clone()).unwrap_or_default(),
+            bind_group_count: self.layout.map(|layout| layout.bind_group_count),
+        })
     }
 }
 
@@ -409,12 +1029,43 @@ impl<'cpb> ComputePipelineBuilder<'cpb>{
 ///
 /// A wrapper for wgpu::RenderPass
 ///
+#[must_use = "a RenderPass records nothing unless commands are set on it"]
 #[derive(DerefMut)]
 pub struct RenderPass<'rp>{
+    #[target]
     pub render_pass: wgpu::RenderPass<'rp>,
+    #[cfg(feature = "render_stats")]
+    pub stats: RenderStats,
+    timestamps: Option<&'rp TimestampQuery>,
+}
+
+impl<'rp> Drop for RenderPass<'rp>{
+    fn drop(&mut self){
+        if let Some(timestamps) = self.timestamps{
+            self.render_pass.write_timestamp(timestamps.query_set(), 1);
+        }
+    }
 }
 
 impl<'rp> RenderPass<'rp>{
+    ///
+    /// Ends the pass and returns the statistics of the draw calls recorded in it.
+    ///
+    #[cfg(feature = "render_stats")]
+    pub fn finish(self) -> RenderStats{
+        self.stats
+    }
+
+    ///
+    /// Ends the pass.
+    ///
+    /// The encoder the pass was begun on is borrowed until the pass is ended, so this has to be
+    /// called (or the pass dropped) before the encoder can be used again.
+    ///
+    #[inline]
+    pub fn end(self){
+        drop(self);
+    }
 
     pub fn set_pipeline(&mut self, pipeline: &'rp RenderPipeline) -> RenderPassPipeline<'rp, '_>{
         self.render_pass.set_pipeline(&pipeline.pipeline);
@@ -424,6 +1075,15 @@ impl<'rp> RenderPass<'rp>{
         }
     }
 
+    ///
+    /// Replays the commands recorded in the render bundles (see RenderBundleBuilder).
+    ///
+    /// The pipeline, bind groups and buffers set before are unset afterwards.
+    ///
+    pub fn execute_bundles(&mut self, render_bundles: &[&'rp wgpu::RenderBundle]){
+        self.render_pass.execute_bundles(render_bundles.iter().copied());
+    }
+
     /* TODO: maybe remove RenderPassPipeline
        #[inline]
        pub fn set_bind_group(&mut self, index: u32, bind_group: &'rp wgpu::BindGroup, offsets: &'rp [wgpu::DynamicOffset]){
@@ -432,36 +1092,246 @@ impl<'rp> RenderPass<'rp>{
        */
 }
 
+///
+/// Records draw calls into a wgpu::RenderBundle that can be replayed in render passes with
+/// RenderPass::execute_bundles instead of encoding them every frame.
+///
+/// The color formats, depth stencil format and sample count have to match the render pass the
+/// bundle is executed in.
+///
+/// ```ignore
+/// let mut bundle = RenderBundleBuilder::new(&gpu.device, &wgpu::RenderBundleEncoderDescriptor{
+///     label: None,
+///     color_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+///     depth_stencil: None,
+///     sample_count: 1,
+///     multiview: None,
+/// });
+/// bundle.set_pipeline(&pipeline);
+/// bundle.set_vertex_buffer(0, vertices.slice(..));
+/// bundle.set_index_buffer(indices.slice(..));
+/// bundle.draw_indexed(0..indices.len() as u32, 0, 0..1);
+/// let bundle = bundle.finish(None);
+///
+/// rpass.execute_bundles(&[&bundle]);
+/// ```
+///
+pub struct RenderBundleBuilder<'rb>{
+    pub encoder: wgpu::RenderBundleEncoder<'rb>,
+    pub pipeline: Option<&'rb RenderPipeline>,
+}
+
+impl<'rb> RenderBundleBuilder<'rb>{
+    pub fn new(device: &'rb wgpu::Device, desc: &wgpu::RenderBundleEncoderDescriptor) -> Self{
+        Self{
+            encoder: device.create_render_bundle_encoder(desc),
+            pipeline: None,
+        }
+    }
+
+    pub fn set_pipeline(&mut self, pipeline: &'rb RenderPipeline){
+        self.encoder.set_pipeline(&pipeline.pipeline);
+        self.pipeline = Some(pipeline);
+    }
+
+    fn bind_group_count(&self) -> Option<u32>{
+        self.pipeline.and_then(|pipeline| pipeline.bind_group_count)
+    }
+
+    pub fn set_bind_group<B: binding::GetBindGroup>(&mut self, index: u32, bind_group: &'rb B, offsets: &'rb [wgpu::DynamicOffset]){
+        debug_assert_bind_group_index(index, self.bind_group_count());
+        self.encoder.set_bind_group(
+            index,
+            bind_group.bind_group(),
+            offsets
+        );
+    }
+
+    pub fn set_bind_groups(&mut self, bind_groups: &[&'rb wgpu::BindGroup]){
+        for (i, bind_group) in bind_groups.iter().enumerate(){
+            debug_assert_bind_group_index(i as u32, self.bind_group_count());
+            self.encoder.set_bind_group(
+                i as u32,
+                bind_group,
+                &[]
+            );
+        }
+    }
+
+    ///
+    /// Sets the push constant at index of the current pipeline.
+    ///
+    /// Panics if no pipeline has been set or if there is no push constant range at index (see
+    /// push_const_range).
+    ///
+    pub fn set_push_const<C: PushConstant>(&mut self, index: usize, constant: &C){
+        let pipeline = self.pipeline.expect("A pipeline has to be set before setting push constants");
+        let range = push_const_range::<C>(&pipeline.push_const_ranges, index);
+        self.encoder.set_push_constants(
+            range.stages,
+            range.range.start,
+            bytemuck::bytes_of(constant));
+    }
+
+    ///
+    /// In debug builds this asserts that the step mode of T matches the step mode of the slot in
+    /// the pipeline.
+    ///
+    pub fn set_vertex_buffer<T: VertLayout>(&mut self, index: u32, buffer_slice: BufferSlice<'rb, T>){
+        if let Some(step_mode) = self.pipeline.and_then(|pipeline| pipeline.step_modes.get(index as usize)){
+            debug_assert_eq!(
+                T::buffer_layout().step_mode, *step_mode,
+                "Step mode of the buffer does not match the step mode of vertex buffer slot {}", index
+            );
+        }
+        self.encoder.set_vertex_buffer(
+            index,
+            buffer_slice.into()
+        );
+    }
+
+    pub fn set_index_buffer(&mut self, buffer_slice: BufferSlice<'rb, u32>){
+        self.encoder.set_index_buffer(buffer_slice.into(), wgpu::IndexFormat::Uint32);
+    }
+
+    pub fn set_index_buffer16(&mut self, buffer_slice: BufferSlice<'rb, u16>){
+        self.encoder.set_index_buffer(buffer_slice.into(), wgpu::IndexFormat::Uint16);
+    }
+
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>){
+        self.encoder.draw(vertices, instances);
+    }
+
+    pub fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>){
+        self.encoder.draw_indexed(indices, base_vertex, instances);
+    }
+
+    pub fn draw_indirect(&mut self, indirect_buffer: &'rb Buffer<DrawIndirect>, indirect_offset: wgpu::BufferAddress){
+        debug_assert_indirect::<DrawIndirect>(indirect_buffer.size(), indirect_offset);
+        self.encoder.draw_indirect(&indirect_buffer.buffer, indirect_offset);
+    }
+
+    pub fn draw_indexed_indirect(&mut self, indirect_buffer: &'rb Buffer<DrawIndexedIndirect>, indirect_offset: wgpu::BufferAddress){
+        debug_assert_indirect::<DrawIndexedIndirect>(indirect_buffer.size(), indirect_offset);
+        self.encoder.draw_indexed_indirect(&indirect_buffer.buffer, indirect_offset);
+    }
+
+    pub fn finish(self, label: wgpu::Label) -> wgpu::RenderBundle{
+        self.encoder.finish(&wgpu::RenderBundleDescriptor{
+            label,
+        })
+    }
+}
+
 ///
 /// A builder for the RenderPass.
 ///
 #[derive(Default)]
 pub struct RenderPassBuilder<'rp>{
     color_attachments: Vec<wgpu::RenderPassColorAttachment<'rp>>,
+    depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachment<'rp>>,
 }
 
 impl<'rp> RenderPassBuilder<'rp>{
     pub fn new() -> Self{
         Self{
             color_attachments: Vec::new(),
+            depth_stencil_attachment: None,
         }
     }
 
+    pub fn set_depth_stencil_attachment(mut self, depth_stencil_attachment: wgpu::RenderPassDepthStencilAttachment<'rp>) -> Self{
+        self.depth_stencil_attachment = Some(depth_stencil_attachment);
+        self
+    }
+
     pub fn push_color_attachment(mut self, color_attachment: wgpu::RenderPassColorAttachment<'rp>) -> Self{
         self.color_attachments.push(color_attachment);
         self
     }
 
-    // TODO: add depth_stencil_attachment
+    ///
+    /// Pushes a color attachment that is cleared with color at the start of the pass.
+    ///
+    pub fn push_color_attachment_clear<C: ColorAttachment>(self, target: &'rp C, color: wgpu::Color) -> Self{
+        self.push_color_attachment(target.color_attachment_clear_with(color))
+    }
+
+    ///
+    /// Pushes a color attachment whose content is loaded at the start of the pass.
+    ///
+    pub fn push_color_attachment_load<C: ColorAttachment>(self, target: &'rp C) -> Self{
+        self.push_color_attachment(target.color_attachment_load())
+    }
+
+    ///
+    /// Pushes a cleared multisampled color attachment that is resolved into resolve at the end of
+    /// the pass.
+    ///
+    pub fn push_color_attachment_resolve<C: ColorAttachment>(self, target: &'rp C, resolve: &'rp wgpu::TextureView) -> Self{
+        self.push_color_attachment(target.color_attachment_clear_resolve(resolve))
+    }
+
     pub fn begin(self, encoder: &'rp mut wgpu::CommandEncoder, label: Option<&'rp str>) -> RenderPass<'rp>{
         RenderPass{
             render_pass: encoder.begin_render_pass(&wgpu::RenderPassDescriptor{
                 label,
                 color_attachments: &self.color_attachments,
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: self.depth_stencil_attachment,
             }),
+            #[cfg(feature = "render_stats")]
+            stats: RenderStats::default(),
+            timestamps: None,
         }
     }
+
+    ///
+    /// Begins a render pass that writes timestamps to timestamps at its start and when it is
+    /// ended or dropped.
+    ///
+    /// Requires the TIMESTAMP_QUERY feature.
+    ///
+    pub fn begin_timed(self, encoder: &'rp mut wgpu::CommandEncoder, timestamps: &'rp TimestampQuery, label: Option<&'rp str>) -> RenderPass<'rp>{
+        let mut rpass = self.begin(encoder, label);
+        rpass.render_pass.write_timestamp(timestamps.query_set(), 0);
+        rpass.timestamps = Some(timestamps);
+        rpass
+    }
+}
+
+///
+/// Records several passes into one CommandEncoder.
+///
+/// Every pass only lives for the duration of its closure, so the next pass can be recorded
+/// without dropping the previous one manually.
+///
+pub struct PassRecorder<'pr>{
+    encoder: &'pr mut wgpu::CommandEncoder,
+}
+
+impl<'pr> PassRecorder<'pr>{
+    pub fn new(encoder: &'pr mut wgpu::CommandEncoder) -> Self{
+        Self{
+            encoder,
+        }
+    }
+
+    pub fn render_pass<'rp, F>(&'rp mut self, builder: RenderPassBuilder<'rp>, label: Option<&'rp str>, f: F)
+        where F: FnOnce(&mut RenderPass<'rp>){
+        let mut rpass = builder.begin(self.encoder, label);
+        f(&mut rpass);
+    }
+
+    pub fn compute_pass<'cp, F>(&'cp mut self, label: Option<&str>, f: F)
+        where F: FnOnce(&mut ComputePass<'cp>){
+        let mut cpass = ComputePass::new(self.encoder, label);
+        f(&mut cpass);
+    }
+
+    #[inline]
+    pub fn encoder(&mut self) -> &mut wgpu::CommandEncoder{
+        self.encoder
+    }
 }
 
 ///
@@ -504,12 +1374,12 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
 
         let vertex = VertexState{
             vertex_buffer_layouts: Vec::new(),
-            entry_point: DEFAULT_ENTRY_POINT,
+            entry_point: vertex_shader.entry_point(),
             shader: vertex_shader,
         };
         let fragment = FragmentState{
             targets: Vec::new(),
-            entry_point: DEFAULT_ENTRY_POINT,
+            entry_point: fragment_shader.entry_point(),
             shader: fragment_shader,
         };
 
@@ -537,6 +1407,11 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Sets the index format of the strip topologies, which enables primitive restart with the
+    /// maximum index value.
+    /// Has to be None for the list topologies.
+    ///
     #[inline]
     pub fn set_strip_index_format(mut self, format: Option<wgpu::IndexFormat>) -> Self{
         self.primitive.strip_index_format = format;
@@ -555,6 +1430,13 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Sets the polygon mode used to rasterize triangles.
+    ///
+    /// PolygonMode::Line requires the POLYGON_MODE_LINE feature, which is enabled by
+    /// GPUContextBuilder::set_features_util, and PolygonMode::Point the POLYGON_MODE_POINT
+    /// feature.
+    ///
     #[inline]
     pub fn set_polygon_mode(mut self, mode: wgpu::PolygonMode) -> Self{
         self.primitive.polygon_mode = mode;
@@ -591,12 +1473,56 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Enables depth testing against an attachment of the given format.
+    ///
+    /// Depth writes are enabled and fragments pass if they are closer (CompareFunction::Less).
+    ///
+    #[inline]
+    pub fn set_depth_format(mut self, format: wgpu::TextureFormat) -> Self{
+        self.depth_stencil = Some(wgpu::DepthStencilState{
+            format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        });
+        self
+    }
+
     #[inline]
     pub fn set_multisample(mut self, multisample: wgpu::MultisampleState) -> Self{
         self.multisample = multisample;
         self
     }
 
+    ///
+    /// Sets the number of samples, has to match the sample count of the attachments.
+    ///
+    #[inline]
+    pub fn sample_count(mut self, count: u32) -> Self{
+        self.multisample.count = count;
+        self
+    }
+
+    ///
+    /// Enables alpha to coverage.
+    ///
+    /// This only has an effect if the pipeline is multisampled (multisample count > 1).
+    ///
+    #[inline]
+    pub fn set_alpha_to_coverage(mut self, alpha_to_coverage_enabled: bool) -> Self{
+        self.multisample.alpha_to_coverage_enabled = alpha_to_coverage_enabled;
+        self
+    }
+
+    ///
+    /// Renders to multiple array layers at once, the shader can read the current layer from
+    /// gl_ViewIndex. Requires the MULTIVIEW feature.
+    ///
+    /// The color and depth attachments have to be array views with the same number of layers
+    /// (see Texture::multiview_view).
+    ///
     #[inline]
     pub fn set_multiview(mut self, multiview: Option<NonZeroU32>) -> Self{
         self.multiview = multiview;
@@ -604,17 +1530,32 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
     }
 
     #[inline]
-    pub fn push_vert_layout(mut self, vertex_buffer_layout: wgpu::VertexBufferLayout<'rpb>) -> Self{
+    pub fn push_vert_layout(mut self, vertex_buffer_layout: wgpu::VertexBufferLayout) -> Self{
         self.vertex = self.vertex.push_vert_layout(vertex_buffer_layout);
         self
     }
 
     #[inline]
-    pub fn push_vert_layouts(mut self, vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout<'rpb>>) -> Self{
+    pub fn push_vert_layouts(mut self, vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout>) -> Self{
         self.vertex = self.vertex.push_vert_layouts(vertex_buffer_layouts);
         self
     }
 
+    #[inline]
+    pub fn push_instance_layout(mut self, instance_buffer_layout: wgpu::VertexBufferLayout) -> Self{
+        self.vertex = self.vertex.push_instance_layout(instance_buffer_layout);
+        self
+    }
+
+    ///
+    /// Pushes a buffer layout with computed offsets and stride (see VertexState::push_interleaved).
+    ///
+    #[inline]
+    pub fn push_interleaved(mut self, step_mode: wgpu::VertexStepMode, attributes: &[wgpu::VertexFormat]) -> Self{
+        self.vertex = self.vertex.push_interleaved(step_mode, attributes);
+        self
+    }
+
     ///
     /// Pushes a RenderTarget to the fragment state.
     ///
@@ -626,6 +1567,51 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Pushes a RenderTarget with alpha blending to the fragment state.
+    ///
+    /// Has to be pushed in the same order as their corresponding color attachements.
+    ///
+    #[inline]
+    pub fn push_target_alpha_blend(mut self, format: wgpu::TextureFormat) -> Self{
+        self.fragment = self.fragment.push_target_alpha_blend(format);
+        self
+    }
+
+    ///
+    /// Pushes a RenderTarget with additive blending to the fragment state.
+    ///
+    /// Has to be pushed in the same order as their corresponding color attachements.
+    ///
+    #[inline]
+    pub fn push_target_additive(mut self, format: wgpu::TextureFormat) -> Self{
+        self.fragment = self.fragment.push_target_additive(format);
+        self
+    }
+
+    ///
+    /// Pushes a RenderTarget with premultiplied alpha blending to the fragment state.
+    ///
+    /// Has to be pushed in the same order as their corresponding color attachements.
+    ///
+    #[inline]
+    pub fn push_target_premultiplied(mut self, format: wgpu::TextureFormat) -> Self{
+        self.fragment = self.fragment.push_target_premultiplied(format);
+        self
+    }
+
+    ///
+    /// Pushes a RenderTarget with the default format (see texture::default_format) to the
+    /// fragment state.
+    ///
+    /// Has to be pushed in the same order as their corresponding color attachements.
+    ///
+    #[inline]
+    pub fn push_target_default(mut self) -> Self{
+        self.fragment = self.fragment.push_target_default();
+        self
+    }
+
     ///
     /// Pushes a RenderTarget to the fragment state.
     ///
@@ -661,7 +1647,28 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
         self
     }
 
+    ///
+    /// Builds the pipeline.
+    ///
+    /// Panics if the builder is invalid (see try_build).
+    ///
     pub fn build(self, device: &wgpu::Device) -> RenderPipeline{
+        self.try_build(device).unwrap()
+    }
+
+    ///
+    /// Builds the pipeline or returns an error if the builder is invalid.
+    ///
+    /// A strip_index_format can only be set for the strip topologies.
+    ///
+    pub fn try_build(self, device: &wgpu::Device) -> Result<RenderPipeline>{
+        ensure!(
+            self.primitive.strip_index_format.is_none() || matches!(
+                self.primitive.topology,
+                wgpu::PrimitiveTopology::LineStrip | wgpu::PrimitiveTopology::TriangleStrip
+            ),
+            "A strip index format was set for the non strip topology {:?}", self.primitive.topology
+        );
 
         let push_const_ranges = match self.layout{
             Some(layout) => layout.push_const_ranges.clone(),
@@ -679,13 +1686,17 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
             targets: &self.fragment.targets,
         };
 
+        let vertex_buffer_layouts: Vec<wgpu::VertexBufferLayout> = self.vertex.vertex_buffer_layouts.iter()
+            .map(|layout| layout.as_wgpu())
+            .collect();
+
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor{
             label: self.label,
             layout,
             vertex: wgpu::VertexState{
                 module: self.vertex.shader,
                 entry_point: self.vertex.entry_point,
-                buffers: &self.vertex.vertex_buffer_layouts,
+                buffers: &vertex_buffer_layouts,
             },
             fragment: Some(fragment),
             primitive: self.primitive,
@@ -694,15 +1705,33 @@ impl<'rpb> RenderPipelineBuilder<'rpb>{
             multiview: self.multiview,
         });
 
-        RenderPipeline{
+        Ok(RenderPipeline{
             pipeline: render_pipeline,
             push_const_ranges,
-        }
+            bind_group_count: self.layout.map(|layout| layout.bind_group_count),
+            step_modes: self.vertex.vertex_buffer_layouts.iter().map(|layout| layout.step_mode).collect(),
+            multiview: self.multiview,
+        })
     }
 }
 
+///
+/// Statistics of the draw calls recorded in a RenderPass.
+///
+/// Only collected if the render_stats feature is enabled.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderStats{
+    pub draw_calls: u32,
+    pub vertices: u64,
+    pub instances: u64,
+}
 
-
-
-// TODO:
-// Counting RenderPass
+impl RenderStats{
+    #[inline]
+    pub fn count_draw(&mut self, vertices: u32, instances: u32){
+        self.draw_calls += 1;
+        self.vertices += vertices as u64 * instances as u64;
+        self.instances += instances as u64;
+    }
+}
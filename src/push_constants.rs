@@ -1,3 +1,6 @@
+use crate::utils::Align;
+use core::ops::Range;
+
 
 ///
 /// A trait implemented by all types that can be used as push constants.
@@ -9,6 +12,48 @@ pub struct PushConstantLayout{
     pub size: u32,
 }
 
+///
+/// Converts push constant layouts to consecutive push constant ranges.
+///
+/// The size of every range is aligned up to 4 bytes as required by wgpu and every range starts
+/// at the end of the previous one.
+///
+pub fn push_const_ranges(push_const_layouts: &[PushConstantLayout]) -> Vec<wgpu::PushConstantRange>{
+    let mut offset = 0;
+    push_const_layouts.iter()
+        .map(|x| {
+            let range = Range::<u32>{
+                start: offset,
+                end: offset + x.size.align_ceil(4),
+            };
+            offset = range.end;
+            wgpu::PushConstantRange{
+                stages: x.stages,
+                range,
+            }
+        }).collect()
+}
+
+///
+/// Returns the push constant range at index into which a C will be written.
+///
+/// Panics with the number of ranges if index is out of bounds.
+/// In debug builds this asserts that C fits into the range, since a larger constant would
+/// overwrite the following range.
+///
+pub fn push_const_range<C: PushConstant>(push_const_ranges: &[wgpu::PushConstantRange], index: usize) -> &wgpu::PushConstantRange{
+    let range = push_const_ranges.get(index).unwrap_or_else(|| panic!(
+        "Push constant index {} is out of bounds, the pipeline has {} push constant range(s)",
+        index, push_const_ranges.len()
+    ));
+    debug_assert!(
+        std::mem::size_of::<C>() as u32 <= range.range.end - range.range.start,
+        "Push constant of {} bytes does not fit into the range {:?} at index {}",
+        std::mem::size_of::<C>(), range.range, index
+    );
+    range
+}
+
 pub trait PushConstant: bytemuck::Pod{
     fn push_const_layout(stages: wgpu::ShaderStages) -> PushConstantLayout;
     fn as_slice8(&self) -> &[u8]{
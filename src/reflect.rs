@@ -0,0 +1,272 @@
+use crate::*;
+use anyhow::{Result, anyhow, bail};
+
+///
+/// Parses WGSL source into a naga module for reflection.
+/// Returns None if the source can not be parsed, the error is reported by wgpu instead.
+///
+pub(crate) fn parse_wgsl(src: &str) -> Option<naga::Module>{
+    naga::front::wgsl::parse_str(src).ok()
+}
+
+///
+/// Parses SPIR-V into a naga module for reflection.
+///
+pub(crate) fn parse_spirv(words: &[u32]) -> Option<naga::Module>{
+    naga::front::spv::parse_u8_slice(bytemuck::cast_slice(words), &Default::default()).ok()
+}
+
+///
+/// The bind group layout entries and push constant layouts used by a set of entry points as
+/// declared in their shaders.
+///
+/// Bindings used by multiple entry points are merged by combining their visibility.
+/// Storage buffers that are only read in some stages become writable if any stage writes to them.
+/// Push constant blocks of all stages are merged into a single range that is visible to every
+/// stage using it, large enough for the largest block.
+///
+#[derive(Default)]
+pub struct ReflectedLayout{
+    pub bind_groups: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    pub push_const_layouts: Vec<PushConstantLayout>,
+}
+
+impl ReflectedLayout{
+    ///
+    /// Reflects the entry points of the modules (see ShaderModule::entry_point).
+    ///
+    /// Returns an error if a module has not been parsed by naga, if the entry point does not
+    /// exist or if the same binding is declared with different types.
+    ///
+    pub fn from_modules(modules: &[&ShaderModule]) -> Result<Self>{
+        let mut layout = Self::default();
+        for module in modules{
+            layout.push_entry_point(module, module.entry_point())?;
+        }
+        Ok(layout)
+    }
+
+    ///
+    /// Adds the resources used by the entry point of the module to the layout.
+    ///
+    pub fn push_entry_point(&mut self, module: &ShaderModule, entry_point: &str) -> Result<()>{
+        let naga_module = module.reflection.as_ref()
+            .ok_or(anyhow!("Shader module has not been parsed for reflection"))?;
+
+        let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+            .validate(naga_module)?;
+
+        let (index, ep) = naga_module.entry_points.iter().enumerate()
+            .find(|(_, ep)| ep.name == entry_point)
+            .ok_or(anyhow!("Entry point \"{}\" not found in shader module", entry_point))?;
+
+        let stage = match ep.stage{
+            naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+            naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+            naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+        };
+        let ep_info = info.get_entry_point(index);
+
+        for (handle, var) in naga_module.global_variables.iter(){
+            if ep_info[handle].is_empty(){
+                continue;
+            }
+
+            if var.class == naga::StorageClass::PushConstant{
+                let size = naga_module.types[var.ty].inner.span(&naga_module.constants);
+                self.push_push_const(stage, size);
+                continue;
+            }
+
+            let binding = match &var.binding{
+                Some(binding) => binding,
+                None => continue,
+            };
+            let ty = binding_type(naga_module, var)?;
+            self.push_binding(binding.group, binding.binding, stage, ty)?;
+        }
+        Ok(())
+    }
+
+    fn push_push_const(&mut self, stage: wgpu::ShaderStages, size: u32){
+        match self.push_const_layouts.first_mut(){
+            Some(layout) => {
+                layout.stages |= stage;
+                layout.size = layout.size.max(size);
+            },
+            None => self.push_const_layouts.push(PushConstantLayout{
+                stages: stage,
+                size,
+            }),
+        }
+    }
+
+    fn push_binding(&mut self, group: u32, binding: u32, stage: wgpu::ShaderStages, ty: wgpu::BindingType) -> Result<()>{
+        if self.bind_groups.len() <= group as usize{
+            self.bind_groups.resize(group as usize + 1, Vec::new());
+        }
+        let entries = &mut self.bind_groups[group as usize];
+
+        match entries.iter_mut().find(|entry| entry.binding == binding){
+            Some(entry) => {
+                entry.ty = merge_binding_types(entry.ty, ty)
+                    .ok_or(anyhow!("Binding {} in group {} is declared with different types: {:?} and {:?}", binding, group, entry.ty, ty))?;
+                entry.visibility |= stage;
+            },
+            None => {
+                entries.push(wgpu::BindGroupLayoutEntry{
+                    binding,
+                    visibility: stage,
+                    ty,
+                    count: None,
+                });
+                entries.sort_by_key(|entry| entry.binding);
+            },
+        }
+        Ok(())
+    }
+
+    ///
+    /// Creates a bind group layout for every group.
+    /// Groups that are not used by any entry point get an empty layout.
+    ///
+    pub fn bind_group_layouts(&self, device: &wgpu::Device) -> Vec<binding::BindGroupLayoutWithDesc>{
+        self.bind_groups.iter()
+            .map(|entries| binding::BindGroupLayoutWithDesc{
+                layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor{
+                    label: None,
+                    entries,
+                }),
+                entries: entries.clone(),
+            }).collect()
+    }
+
+    pub fn build(&self, device: &wgpu::Device, label: wgpu::Label) -> PipelineLayout{
+        let bind_group_layouts = self.bind_group_layouts(device);
+        let bind_group_layouts: Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter()
+            .map(|layout| &layout.layout)
+            .collect();
+
+        PipelineLayout::new(device, &bind_group_layouts, &self.push_const_layouts, label)
+    }
+}
+
+impl PipelineLayout{
+    ///
+    /// Creates a pipeline layout matching the resources used by the entry points of the modules.
+    ///
+    /// See ReflectedLayout for how bindings shared between stages are merged.
+    /// Binding arrays are not supported.
+    ///
+    pub fn reflect(device: &wgpu::Device, modules: &[&ShaderModule], label: wgpu::Label) -> Result<Self>{
+        Ok(ReflectedLayout::from_modules(modules)?.build(device, label))
+    }
+}
+
+fn merge_binding_types(a: wgpu::BindingType, b: wgpu::BindingType) -> Option<wgpu::BindingType>{
+    use wgpu::{BindingType, BufferBindingType};
+    match (a, b){
+        (
+            BindingType::Buffer{ty: BufferBindingType::Storage{read_only: read_only_a}, has_dynamic_offset, min_binding_size},
+            BindingType::Buffer{ty: BufferBindingType::Storage{read_only: read_only_b}, ..}
+        ) => Some(BindingType::Buffer{
+            ty: BufferBindingType::Storage{read_only: read_only_a && read_only_b},
+            has_dynamic_offset,
+            min_binding_size,
+        }),
+        (a, b) if a == b => Some(a),
+        _ => None,
+    }
+}
+
+fn binding_type(module: &naga::Module, var: &naga::GlobalVariable) -> Result<wgpu::BindingType>{
+    Ok(match var.class{
+        naga::StorageClass::Uniform => wgpu::BindingType::Buffer{
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        naga::StorageClass::Storage{access} => wgpu::BindingType::Buffer{
+            ty: wgpu::BufferBindingType::Storage{
+                read_only: !access.contains(naga::StorageAccess::STORE),
+            },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        naga::StorageClass::Handle => match module.types[var.ty].inner{
+            naga::TypeInner::Sampler{comparison} => wgpu::BindingType::Sampler(
+                if comparison{
+                    wgpu::SamplerBindingType::Comparison
+                }
+                else{
+                    wgpu::SamplerBindingType::Filtering
+                }
+            ),
+            naga::TypeInner::Image{dim, arrayed, class} => {
+                let view_dimension = view_dimension(dim, arrayed)?;
+                match class{
+                    naga::ImageClass::Sampled{kind, multi} => wgpu::BindingType::Texture{
+                        sample_type: match kind{
+                            naga::ScalarKind::Float => wgpu::TextureSampleType::Float{filterable: !multi},
+                            naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                            naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                            naga::ScalarKind::Bool => bail!("Boolean textures are not supported"),
+                        },
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Depth{multi} => wgpu::BindingType::Texture{
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension,
+                        multisampled: multi,
+                    },
+                    naga::ImageClass::Storage{format, access} => wgpu::BindingType::StorageTexture{
+                        access: match (access.contains(naga::StorageAccess::LOAD), access.contains(naga::StorageAccess::STORE)){
+                            (true, true) => wgpu::StorageTextureAccess::ReadWrite,
+                            (true, false) => wgpu::StorageTextureAccess::ReadOnly,
+                            _ => wgpu::StorageTextureAccess::WriteOnly,
+                        },
+                        format: storage_format(format)?,
+                        view_dimension,
+                    },
+                }
+            },
+            ref inner => bail!("Unsupported handle type {:?}", inner),
+        },
+        class => bail!("Unsupported storage class {:?} for a binding", class),
+    })
+}
+
+fn view_dimension(dim: naga::ImageDimension, arrayed: bool) -> Result<wgpu::TextureViewDimension>{
+    Ok(match (dim, arrayed){
+        (naga::ImageDimension::D1, false) => wgpu::TextureViewDimension::D1,
+        (naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+        (naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+        (naga::ImageDimension::D3, false) => wgpu::TextureViewDimension::D3,
+        (naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+        (naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+        (dim, true) => bail!("Arrays of {:?} textures are not supported", dim),
+    })
+}
+
+fn storage_format(format: naga::StorageFormat) -> Result<wgpu::TextureFormat>{
+    Ok(match format{
+        naga::StorageFormat::R32Uint => wgpu::TextureFormat::R32Uint,
+        naga::StorageFormat::R32Sint => wgpu::TextureFormat::R32Sint,
+        naga::StorageFormat::R32Float => wgpu::TextureFormat::R32Float,
+        naga::StorageFormat::Rg32Uint => wgpu::TextureFormat::Rg32Uint,
+        naga::StorageFormat::Rg32Sint => wgpu::TextureFormat::Rg32Sint,
+        naga::StorageFormat::Rg32Float => wgpu::TextureFormat::Rg32Float,
+        naga::StorageFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+        naga::StorageFormat::Rgba8Snorm => wgpu::TextureFormat::Rgba8Snorm,
+        naga::StorageFormat::Rgba8Uint => wgpu::TextureFormat::Rgba8Uint,
+        naga::StorageFormat::Rgba8Sint => wgpu::TextureFormat::Rgba8Sint,
+        naga::StorageFormat::Rgba16Uint => wgpu::TextureFormat::Rgba16Uint,
+        naga::StorageFormat::Rgba16Sint => wgpu::TextureFormat::Rgba16Sint,
+        naga::StorageFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        naga::StorageFormat::Rgba32Uint => wgpu::TextureFormat::Rgba32Uint,
+        naga::StorageFormat::Rgba32Sint => wgpu::TextureFormat::Rgba32Sint,
+        naga::StorageFormat::Rgba32Float => wgpu::TextureFormat::Rgba32Float,
+        format => bail!("Storage format {:?} is not supported", format),
+    })
+}
@@ -1,3 +1,5 @@
+use crate::*;
+
 ///
 /// Can be attached as the ColorAttachment of a RenderPass
 ///
@@ -5,6 +7,16 @@ pub trait ColorAttachment{
     fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment;
     fn color_attachment_clear_with(&self, color: wgpu::Color) -> wgpu::RenderPassColorAttachment;
     fn color_attachment_load(&self) -> wgpu::RenderPassColorAttachment;
+
+    ///
+    /// Clears the multisampled attachment and resolves it into resolve_target at the end of the
+    /// pass.
+    ///
+    fn color_attachment_clear_resolve<'a>(&'a self, resolve_target: &'a wgpu::TextureView) -> wgpu::RenderPassColorAttachment<'a>{
+        let mut color_attachment = self.color_attachment_clear();
+        color_attachment.resolve_target = Some(resolve_target);
+        color_attachment
+    }
 }
 
 impl ColorAttachment for wgpu::TextureView{
@@ -47,3 +59,136 @@ impl ColorAttachment for wgpu::TextureView{
     }
 }
 
+
+///
+/// Can be attached as the DepthStencilAttachment of a RenderPass
+///
+pub trait DepthAttachment{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment;
+    fn depth_attachment_clear_with(&self, depth: f32, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment;
+    fn depth_attachment_load_with(&self, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment;
+
+    fn depth_attachment_load(&self) -> wgpu::RenderPassDepthStencilAttachment{
+        self.depth_attachment_load_with(None)
+    }
+}
+
+impl DepthAttachment for wgpu::TextureView{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment{
+        self.depth_attachment_clear_with(1.0, None)
+    }
+
+    fn depth_attachment_clear_with(&self, depth: f32, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        wgpu::RenderPassDepthStencilAttachment{
+            view: self,
+            depth_ops: Some(wgpu::Operations{
+                load: wgpu::LoadOp::Clear(depth),
+                store: true,
+            }),
+            stencil_ops,
+        }
+    }
+
+    fn depth_attachment_load_with(&self, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        wgpu::RenderPassDepthStencilAttachment{
+            view: self,
+            depth_ops: Some(wgpu::Operations{
+                load: wgpu::LoadOp::Load,
+                store: true,
+            }),
+            stencil_ops,
+        }
+    }
+}
+
+impl DepthAttachment for DepthTexture{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_clear()
+    }
+
+    fn depth_attachment_clear_with(&self, depth: f32, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_clear_with(depth, stencil_ops)
+    }
+
+    fn depth_attachment_load_with(&self, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_load_with(stencil_ops)
+    }
+}
+
+impl DepthAttachment for Texture{
+    fn depth_attachment_clear(&self) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_clear()
+    }
+
+    fn depth_attachment_clear_with(&self, depth: f32, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_clear_with(depth, stencil_ops)
+    }
+
+    fn depth_attachment_load_with(&self, stencil_ops: Option<wgpu::Operations<u32>>) -> wgpu::RenderPassDepthStencilAttachment{
+        self.view.depth_attachment_load_with(stencil_ops)
+    }
+}
+
+///
+/// An offscreen target consisting of a color texture and an optional depth texture.
+///
+/// Both textures can be bound after rendering, which makes the target usable as the input of
+/// the next post processing pass.
+///
+pub struct RenderTarget{
+    pub color: Texture,
+    pub color_view: wgpu::TextureView,
+    pub depth: Option<DepthTexture>,
+}
+
+impl RenderTarget{
+    ///
+    /// Creates a target of size with a color texture of format and a depth texture of
+    /// depth_format if it is not None.
+    ///
+    pub fn new(device: &wgpu::Device, size: [u32; 2], format: wgpu::TextureFormat, depth_format: Option<wgpu::TextureFormat>) -> Self{
+        let color = TextureBuilder::new()
+            .clear(size)
+            .format(format)
+            .label(Some("render_target_color"))
+            .build_empty(device);
+
+        let depth = depth_format.map(|depth_format|{
+            DepthTexture::new(TextureBuilder::new()
+                .clear(size)
+                .depth(depth_format)
+                .label(Some("render_target_depth"))
+                .build_empty(device))
+        });
+
+        Self::from_textures(color, depth)
+    }
+
+    pub fn from_textures(color: Texture, depth: Option<DepthTexture>) -> Self{
+        let color_view = color.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self{
+            color,
+            color_view,
+            depth,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> wgpu::Extent3d{
+        self.color.size
+    }
+
+    ///
+    /// Begins a render pass that clears the color texture with clear and the depth texture to 1.
+    ///
+    pub fn begin_render_pass<'rp>(&'rp self, encoder: &'rp mut wgpu::CommandEncoder, clear: wgpu::Color, label: Option<&'rp str>) -> RenderPass<'rp>{
+        let mut builder = RenderPassBuilder::new()
+            .push_color_attachment(self.color_view.color_attachment_clear_with(clear));
+
+        if let Some(depth) = &self.depth{
+            builder = builder.set_depth_stencil_attachment(depth.depth_attachment_clear());
+        }
+
+        builder.begin(encoder, label)
+    }
+}
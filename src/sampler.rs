@@ -0,0 +1,65 @@
+use crate::*;
+
+///
+/// A wrapper for wgpu::Sampler that can be bound independently of a texture, so one sampler can
+/// be shared between many textures (see Texture::texture_view).
+///
+#[derive(DerefMut)]
+pub struct Sampler{
+    #[target]
+    pub sampler: wgpu::Sampler,
+}
+
+impl Sampler{
+    pub fn new(device: &wgpu::Device, desc: &wgpu::SamplerDescriptor) -> Self{
+        Self{
+            sampler: device.create_sampler(desc),
+        }
+    }
+
+    ///
+    /// Creates a sampler with linear filtering and the address mode on all axes.
+    ///
+    pub fn linear(device: &wgpu::Device, address_mode: wgpu::AddressMode) -> Self{
+        Self::new(device, &wgpu::SamplerDescriptor{
+            label: Some("linear_sampler"),
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Creates a sampler with nearest filtering and the address mode on all axes.
+    ///
+    pub fn nearest(device: &wgpu::Device, address_mode: wgpu::AddressMode) -> Self{
+        Self::new(device, &wgpu::SamplerDescriptor{
+            label: Some("nearest_sampler"),
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        })
+    }
+}
+
+impl BindGroupContent for Sampler{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry>{
+        vec![
+            BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::sampler()),
+        ]
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource>{
+        vec![
+            wgpu::BindingResource::Sampler(&self.sampler),
+        ]
+    }
+}
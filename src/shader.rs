@@ -17,10 +17,198 @@ pub struct ShaderModule{
     #[target]
     pub module: wgpu::ShaderModule,
     pub src_files: Vec<PathBuf>,
+    /// The naga module used by ReflectedLayout, None if naga could not parse the shader.
+    pub reflection: Option<naga::Module>,
+    entry_point: String,
 }
 
 impl ShaderModule{
-    pub fn from_src(device: &wgpu::Device, src: &str, kind: shaderc::ShaderKind, entry_point: &str, label: Option<&str>) -> Result<Self>{
+    ///
+    /// Creates a ShaderModule from WGSL source code.
+    ///
+    /// If src_path is given it is tracked in src_files so that the module can be reloaded.
+    ///
+    pub fn from_src_wgsl(device: &wgpu::Device, src: &str, entry_point: &str, src_path: Option<&Path>, label: Option<&str>) -> Result<Self>{
+        let src_files = match src_path{
+            Some(path) => vec![path.canonicalize()?],
+            None => Vec::new(),
+        };
+
+        let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+            label,
+            source: wgpu::ShaderSource::Wgsl(Cow::from(src)),
+        });
+
+        Ok(ShaderModule{
+            module,
+            src_files,
+            reflection: reflect::parse_wgsl(src),
+            entry_point: String::from(entry_point),
+        })
+    }
+
+    ///
+    /// Loads a WGSL shader resolving `#include "file"` directives relative to the including file.
+    ///
+    /// All resolved files are tracked in src_files.
+    /// Returns an error if a file includes itself directly or indirectly.
+    ///
+    pub fn load_wgsl(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        let mut src_files = Vec::new();
+        let src = Self::resolve_wgsl_includes(path, &mut Vec::new(), &mut src_files)?;
+
+        let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+            label,
+            source: wgpu::ShaderSource::Wgsl(Cow::from(&src)),
+        });
+
+        Ok(ShaderModule{
+            module,
+            src_files,
+            reflection: reflect::parse_wgsl(&src),
+            entry_point: String::from(entry_point),
+        })
+    }
+
+    fn resolve_wgsl_includes(path: &Path, stack: &mut Vec<PathBuf>, src_files: &mut Vec<PathBuf>) -> Result<String>{
+        let path = path.canonicalize()
+            .with_context(|| format!("Failed to resolve shader file {:?}", path))?;
+
+        if stack.contains(&path){
+            bail!("Include cycle detected: {:?} includes itself", path);
+        }
+
+        let src = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read shader file {:?}", path))?;
+
+        if !src_files.contains(&path){
+            src_files.push(path.clone());
+        }
+        stack.push(path.clone());
+
+        let dir = path.parent().unwrap();
+        let mut out = String::with_capacity(src.len());
+        for line in src.lines(){
+            match line.trim().strip_prefix("#include"){
+                Some(name) => {
+                    let name = name.trim().trim_matches('"');
+                    out.push_str(&Self::resolve_wgsl_includes(&dir.join(name), stack, src_files)?);
+                },
+                None => {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+
+        stack.pop();
+        Ok(out)
+    }
+
+    ///
+    /// Creates a ShaderModule from precompiled SPIR-V.
+    ///
+    pub fn from_spirv(device: &wgpu::Device, words: &[u32], entry_point: &str, label: Option<&str>) -> Result<Self>{
+        ensure!(!words.is_empty(), "SPIR-V module is empty");
+
+        let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+            label,
+            source: wgpu::ShaderSource::SpirV(Cow::from(words)),
+        });
+
+        Ok(ShaderModule{
+            module,
+            src_files: Vec::new(),
+            reflection: reflect::parse_spirv(words),
+            entry_point: String::from(entry_point),
+        })
+    }
+
+    ///
+    /// Loads a precompiled SPIR-V file.
+    ///
+    /// Returns an error if the length of the file is not a multiple of 4.
+    ///
+    pub fn load_spirv(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        let path = path.canonicalize()
+            .with_context(|| format!("Failed to resolve shader file {:?}", path))?;
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("Failed to read shader file {:?}", path))?;
+
+        ensure!(bytes.len() % 4 == 0, "Length of SPIR-V file {:?} is not a multiple of 4", path);
+
+        let words: Vec<u32> = bytes.chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+
+        let mut module = Self::from_spirv(device, &words, entry_point, label)?;
+        module.src_files.push(path);
+        Ok(module)
+    }
+
+    ///
+    /// File extensions understood by load_auto.
+    ///
+    pub const SUPPORTED_EXTENSIONS: &'static [&'static str] = &["wgsl", "spv", "vert", "frag", "comp"];
+
+    ///
+    /// Infers the shader stage of a GLSL file from its extension (.vert, .frag or .comp).
+    ///
+    pub fn kind_from_path(path: &Path) -> Option<shaderc::ShaderKind>{
+        match path.extension()?.to_str()?{
+            "vert" => Some(shaderc::ShaderKind::Vertex),
+            "frag" => Some(shaderc::ShaderKind::Fragment),
+            "comp" => Some(shaderc::ShaderKind::Compute),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Loads a shader choosing the loader from the file extension.
+    ///
+    /// WGSL (.wgsl) and SPIR-V (.spv) are loaded directly, GLSL files are compiled with shaderc
+    /// inferring the stage from their extension (.vert, .frag, .comp).
+    /// Returns an error listing the supported extensions for any other file.
+    ///
+    pub fn load_auto(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        match extension{
+            "wgsl" => Self::load_wgsl(device, path, entry_point, label),
+            "spv" => Self::load_spirv(device, path, entry_point, label),
+            _ => match Self::kind_from_path(path){
+                Some(kind) => Self::load(device, path, kind, entry_point, &[], label),
+                None => bail!(
+                    "Could not infer the shader type of {:?} from the extension \"{}\", supported extensions are: {}",
+                    path, extension, Self::SUPPORTED_EXTENSIONS.join(", ")
+                ),
+            }
+        }
+    }
+
+    ///
+    /// Converts a shaderc error to an error containing the full compilation log of shaderc.
+    ///
+    fn compile_error(name: &str, kind: shaderc::ShaderKind, err: shaderc::Error) -> Error{
+        match err{
+            shaderc::Error::CompilationError(count, log) => {
+                anyhow!("Failed to compile {:?} shader \"{}\" with {} error(s):\n{}", kind, name, count, log)
+            },
+            err => anyhow!("Failed to compile {:?} shader \"{}\": {}", kind, name, err),
+        }
+    }
+
+    #[inline]
+    pub fn entry_point(&self) -> &str{
+        &self.entry_point
+    }
+
+    ///
+    /// Compiles GLSL source code using shaderc.
+    ///
+    /// Additionally to VERTEX_SHADER, FRAGMENT_SHADER and COMPUTE_SHADER the macros in defines are
+    /// defined.
+    ///
+    pub fn from_src(device: &wgpu::Device, src: &str, kind: shaderc::ShaderKind, entry_point: &str, defines: &[(&str, Option<&str>)], label: Option<&str>) -> Result<Self>{
         let mut compiler = shaderc::Compiler::new().ok_or(anyhow!("error creating compiler"))?;
         let mut options = shaderc::CompileOptions::new().ok_or(anyhow!("error creating shaderc options"))?;
 
@@ -32,11 +220,13 @@ impl ShaderModule{
         options.add_macro_definition("VERTEX_SHADER", Some(if kind == shaderc::ShaderKind::Vertex {"1"} else {"0"}));
         options.add_macro_definition("FRAGMENT_SHADER", Some(if kind == shaderc::ShaderKind::Fragment {"1"} else {"0"}));
         options.add_macro_definition("COMPUTE_SHADER", Some(if kind == shaderc::ShaderKind::Compute {"1"} else {"0"}));
+        for (name, value) in defines{
+            options.add_macro_definition(name, *value);
+        }
 
-        let spirv = match label{
-            Some(label) => compiler.compile_into_spirv(src, kind, label, entry_point, Some(&options))?,
-            _ => compiler.compile_into_spirv(src, kind, "no_label", entry_point, Some(&options))?,
-        };
+        let name = label.unwrap_or("no_label");
+        let spirv = compiler.compile_into_spirv(src, kind, name, entry_point, Some(&options))
+            .map_err(|err| Self::compile_error(name, kind, err))?;
 
         let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
             label,
@@ -46,22 +236,30 @@ impl ShaderModule{
         Ok(ShaderModule{
             module,
             src_files: Vec::new(),
+            reflection: reflect::parse_spirv(spirv.as_binary()),
+            entry_point: String::from(entry_point),
         })
     }
 
-    pub fn load(device: &wgpu::Device, path: &Path, kind: shaderc::ShaderKind, entry_point: &str, label: Option<&str>) -> Result<Self>{
+    ///
+    /// Loads and compiles a GLSL file using shaderc resolving includes.
+    ///
+    /// Additionally to VERTEX_SHADER, FRAGMENT_SHADER and COMPUTE_SHADER the macros in defines are
+    /// defined.
+    ///
+    pub fn load(device: &wgpu::Device, path: &Path, kind: shaderc::ShaderKind, entry_point: &str, defines: &[(&str, Option<&str>)], label: Option<&str>) -> Result<Self>{
 
-        let src_files = RefCell::new(vec![PathBuf::from(path).canonicalize().unwrap()]);
+        let path = path.canonicalize()
+            .with_context(|| format!("Failed to resolve shader file {:?}", path))?;
+        let dir = path.parent()
+            .ok_or(anyhow!("Shader file {:?} has no parent directory", path))?
+            .to_path_buf();
+        let src = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read shader file {:?}", path))?;
 
-        let module = {
-            let path = src_files.borrow();
-            let dir = path[0].parent().unwrap();
-            let src = match std::fs::read_to_string(&src_files.borrow()[0]){
-                std::result::Result::Ok(src) => src,
-                Err(err) => {
-                    return Err(anyhow!("Failed to read shader file \"{:?}\": {}", &src_files.borrow()[0], err));
-                }
-            };
+        let src_files = RefCell::new(vec![path.clone()]);
+
+        let (module, reflection) = {
 
             let mut compiler = shaderc::Compiler::new().ok_or(anyhow!("error creating compiler"))?;
             let mut options = shaderc::CompileOptions::new().ok_or(anyhow!("error creating shaderc options"))?;
@@ -74,17 +272,19 @@ impl ShaderModule{
             options.add_macro_definition("VERTEX_SHADER", Some(if kind == shaderc::ShaderKind::Vertex {"1"} else {"0"}));
             options.add_macro_definition("FRAGMENT_SHADER", Some(if kind == shaderc::ShaderKind::Fragment {"1"} else {"0"}));
             options.add_macro_definition("COMPUTE_SHADER", Some(if kind == shaderc::ShaderKind::Compute {"1"} else {"0"}));
+            for (name, value) in defines{
+                options.add_macro_definition(name, *value);
+            }
 
             options.set_include_callback(|name, include_type, source_file, _depth| {
-                let path = if include_type == shaderc::IncludeType::Relative{
-                    Path::new(Path::new(source_file).parent().unwrap()).join(name)
-                } else{
-                    dir.join(name)
+                let path = match Path::new(source_file).parent(){
+                    Some(parent) if include_type == shaderc::IncludeType::Relative => parent.join(name),
+                    _ => dir.join(name),
                 };
 
                 match std::fs::read_to_string(&path){
                     std::result::Result::Ok(glsl_code) => {
-                        src_files.borrow_mut().push(path.canonicalize().unwrap());
+                        src_files.borrow_mut().push(path.canonicalize().unwrap_or_else(|_| path.clone()));
                         std::result::Result::Ok(shaderc::ResolvedInclude{
                             resolved_name: String::from(name),
                             content: glsl_code,
@@ -97,17 +297,21 @@ impl ShaderModule{
                 }
             });
 
-            let spirv = compiler.compile_into_spirv(&src, kind, src_files.borrow()[0].to_str().ok_or("Path could not be converted to string").unwrap(), entry_point, Some(&options))?;
+            let name = path.to_str().ok_or(anyhow!("Path {:?} could not be converted to string", path))?;
+            let spirv = compiler.compile_into_spirv(&src, kind, name, entry_point, Some(&options))
+                .map_err(|err| Self::compile_error(name, kind, err))?;
 
             let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
                 label,
                 source: wgpu::ShaderSource::SpirV(Cow::from(spirv.as_binary()))
             });
-            module
+            (module, reflect::parse_spirv(spirv.as_binary()))
         };
         Ok(ShaderModule{
             module,
             src_files: src_files.into_inner(),
+            reflection,
+            entry_point: String::from(entry_point),
         })
     }
 }
@@ -120,13 +324,31 @@ pub struct FragmentShader{
 impl FragmentShader{
     pub fn from_src(device: &wgpu::Device, src: &str, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Fragment, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Fragment, DEFAULT_ENTRY_POINT, &[], label)?,
         })
     }
 
     pub fn load(device: &wgpu::Device, path: &Path, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::load(device, path, shaderc::ShaderKind::Fragment, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::load(device, path, shaderc::ShaderKind::Fragment, DEFAULT_ENTRY_POINT, &[], label)?,
+        })
+    }
+
+    ///
+    /// Creates the shader from WGSL source with the given entry point.
+    ///
+    pub fn from_wgsl(device: &wgpu::Device, src: &str, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::from_src_wgsl(device, src, entry_point, None, label)?,
+        })
+    }
+
+    ///
+    /// Loads the shader from a WGSL file with the given entry point (see ShaderModule::load_wgsl).
+    ///
+    pub fn load_wgsl(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::load_wgsl(device, path, entry_point, label)?,
         })
     }
 }
@@ -139,12 +361,30 @@ pub struct VertexShader{
 impl VertexShader{
     pub fn from_src(device: &wgpu::Device, src: &str, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Vertex, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Vertex, DEFAULT_ENTRY_POINT, &[], label)?,
         })
     }
     pub fn load(device: &wgpu::Device, path: &Path, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::load(device, path, shaderc::ShaderKind::Vertex, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::load(device, path, shaderc::ShaderKind::Vertex, DEFAULT_ENTRY_POINT, &[], label)?,
+        })
+    }
+
+    ///
+    /// Creates the shader from WGSL source with the given entry point.
+    ///
+    pub fn from_wgsl(device: &wgpu::Device, src: &str, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::from_src_wgsl(device, src, entry_point, None, label)?,
+        })
+    }
+
+    ///
+    /// Loads the shader from a WGSL file with the given entry point (see ShaderModule::load_wgsl).
+    ///
+    pub fn load_wgsl(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::load_wgsl(device, path, entry_point, label)?,
         })
     }
 }
@@ -157,12 +397,30 @@ pub struct ComputeShader{
 impl ComputeShader{
     pub fn from_src(device: &wgpu::Device, src: &str, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::from_src(device, src, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, &[], label)?,
         })
     }
     pub fn load(device: &wgpu::Device, path: &Path, label: Option<&str>) -> Result<Self>{
         Ok(Self{
-            module: ShaderModule::load(device, path, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, label)?,
+            module: ShaderModule::load(device, path, shaderc::ShaderKind::Compute, DEFAULT_ENTRY_POINT, &[], label)?,
+        })
+    }
+
+    ///
+    /// Creates the shader from WGSL source with the given entry point.
+    ///
+    pub fn from_wgsl(device: &wgpu::Device, src: &str, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::from_src_wgsl(device, src, entry_point, None, label)?,
+        })
+    }
+
+    ///
+    /// Loads the shader from a WGSL file with the given entry point (see ShaderModule::load_wgsl).
+    ///
+    pub fn load_wgsl(device: &wgpu::Device, path: &Path, entry_point: &str, label: Option<&str>) -> Result<Self>{
+        Ok(Self{
+            module: ShaderModule::load_wgsl(device, path, entry_point, label)?,
         })
     }
 }
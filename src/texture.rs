@@ -1,8 +1,103 @@
 use image::GenericImageView;
 use crate::*;
-use crate::utils::RangeClamp;
+use crate::utils::{RangeClamp, Align};
 use std::fs;
 use std::ops::RangeBounds;
+use std::marker::PhantomData;
+use std::sync::RwLock;
+use anyhow::*;
+use storage_format::StorageTextureFormat;
+use storage_access::StorageTextureAccess;
+
+static DEFAULT_FORMAT: RwLock<wgpu::TextureFormat> = RwLock::new(wgpu::TextureFormat::Rgba8Unorm);
+
+///
+/// Sets the format used by TextureBuilder::new and push_target_default.
+///
+/// Should be called once at the start of the program before any textures or pipelines are
+/// created. The initial default format is Rgba8Unorm.
+///
+pub fn set_default_format(format: wgpu::TextureFormat){
+    *DEFAULT_FORMAT.write().unwrap() = format;
+}
+
+///
+/// Returns the format used by TextureBuilder::new and push_target_default.
+///
+#[inline]
+pub fn default_format() -> wgpu::TextureFormat{
+    *DEFAULT_FORMAT.read().unwrap()
+}
+
+///
+/// Returns the preferred format of the surface for the adapter so that render targets can match
+/// the swapchain.
+///
+/// Falls back to the default format if the surface is incompatible with the adapter.
+///
+pub fn surface_preferred_format(adapter: &wgpu::Adapter, surface: &wgpu::Surface) -> wgpu::TextureFormat{
+    surface.get_preferred_format(adapter).unwrap_or_else(default_format)
+}
+
+///
+/// Returns the number of bytes of one texel block of the format.
+/// For uncompressed formats this is the number of bytes per pixel.
+///
+#[inline]
+pub fn bytes_per_pixel(format: wgpu::TextureFormat) -> u32{
+    format.describe().block_size as u32
+}
+
+///
+/// Returns the width and height in pixels of one texel block of the format.
+/// Uncompressed formats have a block dimension of (1, 1).
+///
+#[inline]
+pub fn block_dimensions(format: wgpu::TextureFormat) -> (u32, u32){
+    let (width, height) = format.describe().block_dimensions;
+    (width as u32, height as u32)
+}
+
+///
+/// Returns the unpadded number of bytes of one row of blocks with the given width in pixels.
+///
+#[inline]
+pub(crate) fn bytes_per_row(format: wgpu::TextureFormat, width: u32) -> u32{
+    let (block_width, _) = block_dimensions(format);
+    ((width + block_width - 1) / block_width) * bytes_per_pixel(format)
+}
+
+///
+/// The number of mip levels of a full mip chain down to 1x1 for a 2D texture of width and height.
+///
+pub fn max_mip_level_count(width: u32, height: u32) -> u32{
+    32 - width.max(height).leading_zeros()
+}
+
+///
+/// Converts the VkFormat of a KTX2 file to a TextureFormat.
+///
+fn ktx2_format(vk_format: u32) -> Result<wgpu::TextureFormat>{
+    Ok(match vk_format{
+        37 => wgpu::TextureFormat::Rgba8Unorm,
+        43 => wgpu::TextureFormat::Rgba8UnormSrgb,
+        131 | 133 => wgpu::TextureFormat::Bc1RgbaUnorm,
+        132 | 134 => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+        135 => wgpu::TextureFormat::Bc2RgbaUnorm,
+        136 => wgpu::TextureFormat::Bc2RgbaUnormSrgb,
+        137 => wgpu::TextureFormat::Bc3RgbaUnorm,
+        138 => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+        139 => wgpu::TextureFormat::Bc4RUnorm,
+        140 => wgpu::TextureFormat::Bc4RSnorm,
+        141 => wgpu::TextureFormat::Bc5RgUnorm,
+        142 => wgpu::TextureFormat::Bc5RgSnorm,
+        143 => wgpu::TextureFormat::Bc6hRgbUfloat,
+        144 => wgpu::TextureFormat::Bc6hRgbSfloat,
+        145 => wgpu::TextureFormat::Bc7RgbaUnorm,
+        146 => wgpu::TextureFormat::Bc7RgbaUnormSrgb,
+        vk_format => bail!("Unsupported VkFormat {} in KTX2 file", vk_format),
+    })
+}
 
 pub trait IntoExtent3D{
     fn into_extent_3d(self) -> wgpu::Extent3d;
@@ -81,15 +176,281 @@ impl IntoExtent3D for wgpu::Extent3d{
     }
 }
 
+///
+/// A WGSL shader drawing a fullscreen triangle and sampling a texture.
+/// Vertex entry point is vs_main, fragment entry point is fs_main.
+///
+pub(crate) const BLIT_SHADER: &str = "
+struct VertexOutput{
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> VertexOutput{
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    out.pos = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var t_src: texture_2d<f32>;
+[[group(0), binding(1)]]
+var s_src: sampler;
+
+[[stage(fragment)]]
+fn fs_main(vertex: VertexOutput) -> [[location(0)]] vec4<f32>{
+    return textureSample(t_src, s_src, vertex.uv);
+}
+";
+
+///
+/// Functions shared by the image based lighting shaders.
+/// Contains a fullscreen triangle vertex shader and GGX importance sampling.
+///
+pub(crate) const IBL_COMMON_SHADER: &str = "
+let PI: f32 = 3.14159265359;
+let SAMPLE_COUNT: u32 = 1024u;
+
+struct VertexOutput{
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> VertexOutput{
+    var out: VertexOutput;
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    out.pos = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn radical_inverse(index: u32) -> f32{
+    var bits: u32 = index;
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return f32(bits) * 2.3283064365386963e-10;
+}
+
+fn hammersley(i: u32, n: u32) -> vec2<f32>{
+    return vec2<f32>(f32(i) / f32(n), radical_inverse(i));
+}
+
+// Samples a half vector around the normal (0, 0, 1).
+fn importance_sample_ggx(xi: vec2<f32>, roughness: f32) -> vec3<f32>{
+    let a = roughness * roughness;
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    return vec3<f32>(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+}
+";
+
+///
+/// Integrates the split sum approximation of the specular BRDF for image based lighting.
+/// x: dot(n, v), y: roughness, output: scale and bias to F0.
+/// Has to be appended to IBL_COMMON_SHADER.
+///
+pub(crate) const BRDF_LUT_SHADER: &str = "
+fn geometry_schlick_ggx(n_dot_v: f32, roughness: f32) -> f32{
+    let k = (roughness * roughness) / 2.0;
+    return n_dot_v / (n_dot_v * (1.0 - k) + k);
+}
+
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32{
+    return geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+}
+
+[[stage(fragment)]]
+fn fs_main(vertex: VertexOutput) -> [[location(0)]] vec2<f32>{
+    let n_dot_v = max(vertex.uv.x, 0.001);
+    let roughness = vertex.uv.y;
+    let v = vec3<f32>(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+
+    var scale: f32 = 0.0;
+    var bias: f32 = 0.0;
+    var i: u32 = 0u;
+    loop{
+        if (i >= SAMPLE_COUNT){
+            break;
+        }
+
+        let h = importance_sample_ggx(hammersley(i, SAMPLE_COUNT), roughness);
+        let l = normalize(2.0 * dot(v, h) * h - v);
+
+        let n_dot_l = max(l.z, 0.0);
+        let n_dot_h = max(h.z, 0.0001);
+        let v_dot_h = max(dot(v, h), 0.0);
+
+        if (n_dot_l > 0.0){
+            let g = geometry_smith(n_dot_v, n_dot_l, roughness);
+            let g_vis = (g * v_dot_h) / (n_dot_h * n_dot_v);
+            let fc = pow(1.0 - v_dot_h, 5.0);
+
+            scale = scale + (1.0 - fc) * g_vis;
+            bias = bias + fc * g_vis;
+        }
+
+        continuing{
+            i = i + 1u;
+        }
+    }
+    return vec2<f32>(scale, bias) / f32(SAMPLE_COUNT);
+}
+";
+
+///
+/// Bindings and helpers shared by the shaders rendering one face of a cube map from an
+/// environment cube map.
+/// Has to be appended to IBL_COMMON_SHADER.
+///
+pub(crate) const IBL_CUBE_SHADER: &str = "
+struct Params{
+    face: u32;
+    roughness: f32;
+    pad0: f32;
+    pad1: f32;
+};
+
+[[group(0), binding(0)]]
+var t_env: texture_cube<f32>;
+[[group(0), binding(1)]]
+var s_env: sampler;
+[[group(0), binding(2)]]
+var<uniform> params: Params;
+
+// Direction of a texel on a cube face with uv in [0, 1] and y pointing down.
+fn cube_dir(face: u32, uv: vec2<f32>) -> vec3<f32>{
+    let c = uv * 2.0 - 1.0;
+    var dir: vec3<f32>;
+    if (face == 0u){
+        dir = vec3<f32>(1.0, -c.y, -c.x);
+    } else if (face == 1u){
+        dir = vec3<f32>(-1.0, -c.y, c.x);
+    } else if (face == 2u){
+        dir = vec3<f32>(c.x, 1.0, c.y);
+    } else if (face == 3u){
+        dir = vec3<f32>(c.x, -1.0, -c.y);
+    } else if (face == 4u){
+        dir = vec3<f32>(c.x, -c.y, 1.0);
+    } else{
+        dir = vec3<f32>(-c.x, -c.y, -1.0);
+    }
+    return normalize(dir);
+}
+
+// Tangent space around the normal n.
+fn tangent_frame(n: vec3<f32>) -> mat3x3<f32>{
+    var up = vec3<f32>(0.0, 0.0, 1.0);
+    if (abs(n.z) > 0.999){
+        up = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    let tangent = normalize(cross(up, n));
+    let bitangent = cross(n, tangent);
+    return mat3x3<f32>(tangent, bitangent, n);
+}
+";
+
+///
+/// Convolves one face of an environment cube map with the GGX distribution of a roughness.
+/// Has to be appended to IBL_COMMON_SHADER and IBL_CUBE_SHADER.
+///
+pub(crate) const PREFILTER_ENV_SHADER: &str = "
+[[stage(fragment)]]
+fn fs_main(vertex: VertexOutput) -> [[location(0)]] vec4<f32>{
+    let n = cube_dir(params.face, vertex.uv);
+    let v = n;
+    let frame = tangent_frame(n);
+
+    var color = vec3<f32>(0.0, 0.0, 0.0);
+    var weight: f32 = 0.0;
+    var i: u32 = 0u;
+    loop{
+        if (i >= SAMPLE_COUNT){
+            break;
+        }
+
+        let h_t = importance_sample_ggx(hammersley(i, SAMPLE_COUNT), params.roughness);
+        let h = normalize(frame * h_t);
+        let l = normalize(2.0 * dot(v, h) * h - v);
+
+        let n_dot_l = dot(n, l);
+        if (n_dot_l > 0.0){
+            color = color + textureSampleLevel(t_env, s_env, l, 0.0).rgb * n_dot_l;
+            weight = weight + n_dot_l;
+        }
+
+        continuing{
+            i = i + 1u;
+        }
+    }
+    return vec4<f32>(color / max(weight, 0.0001), 1.0);
+}
+";
+
+///
+/// Convolves one face of an environment cube map with a cosine lobe to get the diffuse irradiance.
+/// The result is divided by PI so that it only has to be multiplied with the albedo.
+/// Has to be appended to IBL_COMMON_SHADER and IBL_CUBE_SHADER.
+///
+pub(crate) const IRRADIANCE_SHADER: &str = "
+// Samples a direction around the normal (0, 0, 1) proportional to the cosine.
+fn sample_cosine(xi: vec2<f32>) -> vec3<f32>{
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt(1.0 - xi.y);
+    let sin_theta = sqrt(xi.y);
+    return vec3<f32>(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+}
+
+[[stage(fragment)]]
+fn fs_main(vertex: VertexOutput) -> [[location(0)]] vec4<f32>{
+    let frame = tangent_frame(cube_dir(params.face, vertex.uv));
+
+    var irradiance = vec3<f32>(0.0, 0.0, 0.0);
+    var i: u32 = 0u;
+    loop{
+        if (i >= SAMPLE_COUNT){
+            break;
+        }
+
+        let l = normalize(frame * sample_cosine(hammersley(i, SAMPLE_COUNT)));
+        irradiance = irradiance + textureSampleLevel(t_env, s_env, l, 0.0).rgb;
+
+        continuing{
+            i = i + 1u;
+        }
+    }
+    return vec4<f32>(irradiance / f32(SAMPLE_COUNT), 1.0);
+}
+";
+
+#[repr(C)]
+#[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PrefilterParams{
+    face: u32,
+    roughness: f32,
+    _pad: [f32; 2],
+}
+
 ///
 /// 
 ///
 pub struct Texture{
     pub texture: wgpu::Texture,
-    //pub view: wgpu::TextureView,
+    /// View of all mip levels and layers, used when attaching the texture to a render pass.
+    pub view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
     pub format: wgpu::TextureFormat,
     pub size: wgpu::Extent3d,
+    pub mip_level_count: u32,
+    pub dimension: wgpu::TextureDimension,
+    pub usage: wgpu::TextureUsages,
 }
 
 pub struct TextureSlice<'ts>{
@@ -99,6 +460,16 @@ pub struct TextureSlice<'ts>{
 }
 
 impl<'ts> TextureSlice<'ts>{
+    #[inline]
+    pub fn origin(&self) -> wgpu::Origin3d{
+        self.origin
+    }
+
+    #[inline]
+    pub fn extent(&self) -> wgpu::Extent3d{
+        self.extent
+    }
+
     pub fn copy_to_texture(&self, encoder: &mut wgpu::CommandEncoder, dst: &Texture, offset: wgpu::Origin3d){
         encoder.copy_texture_to_texture(
             wgpu::ImageCopyTexture{
@@ -117,7 +488,24 @@ impl<'ts> TextureSlice<'ts>{
         );
     }
 
-    pub fn copy_to_buffer<C: bytemuck::Pod>(&self, encoder: &mut wgpu::CommandEncoder, dst: &mut Buffer<C>, offset: wgpu::BufferAddress){
+    ///
+    /// Copies the slice to a buffer.
+    ///
+    /// Every row in the buffer is padded to a multiple of wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+    /// (256 bytes), so the destination buffer has to be large enough to hold the padded rows.
+    /// The returned layout describes where the rows are located in the buffer and can be
+    /// used to remove the padding after mapping.
+    ///
+    pub fn copy_to_buffer<C: bytemuck::Pod>(&self, encoder: &mut wgpu::CommandEncoder, dst: &mut Buffer<C>, offset: wgpu::BufferAddress) -> wgpu::ImageDataLayout{
+        let bytes_per_row = bytes_per_row(self.texture.format, self.extent.width)
+            .align_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let layout = wgpu::ImageDataLayout{
+            offset,
+            bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+            rows_per_image: std::num::NonZeroU32::new(self.extent.height),
+        };
+
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture{
                 texture: &self.texture.texture,
@@ -127,14 +515,41 @@ impl<'ts> TextureSlice<'ts>{
             },
             wgpu::ImageCopyBuffer{
                 buffer: &dst.buffer,
-                layout: wgpu::ImageDataLayout{
-                    offset,
-                    bytes_per_row: std::num::NonZeroU32::new(self.texture.size.width * self.texture.format.describe().block_size as u32),
-                    rows_per_image: std::num::NonZeroU32::new(self.texture.size.height),
-                }
+                layout,
             },
             self.extent
         );
+        layout
+    }
+
+    ///
+    /// Reads the slice back to the cpu.
+    ///
+    /// The returned data is tightly packed, the row padding required by the copy is removed.
+    ///
+    pub async fn read_to_vec(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Vec<u8>>{
+        let bytes_per_row = bytes_per_row(self.texture.format, self.extent.width) as usize;
+        let padded_bytes_per_row = (bytes_per_row as u32).align_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) as usize;
+        let rows = (self.extent.height * self.extent.depth_or_array_layers) as usize;
+
+        ensure!(bytes_per_row * rows > 0, "Cannot read back an empty texture slice");
+
+        let mut staging = BufferBuilder::<u8>::new()
+            .copy_dst().read()
+            .set_label(Some("texture_readback"))
+            .build_empty(device, padded_bytes_per_row * rows);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("texture_readback")});
+        self.copy_to_buffer(&mut encoder, &mut staging, 0);
+        queue.submit(Some(encoder.finish()));
+
+        let view = staging.slice(..).map_async_poll(device).await;
+
+        let mut data = Vec::with_capacity(bytes_per_row * rows);
+        for row in view.chunks(padded_bytes_per_row){
+            data.extend_from_slice(&row[..bytes_per_row]);
+        }
+        Ok(data)
     }
 
     pub fn to_image(&self, device: &wgpu::Device) -> image::DynamicImage{
@@ -153,6 +568,19 @@ impl<'ts> TextureSlice<'ts>{
     }
 }
 
+///
+/// High level description of how a Texture should be sampled.
+///
+/// Anisotropic filtering requires the anisotropy to be one of 1, 2, 4, 8 or 16.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureQuality{
+    Pixelated,
+    Bilinear,
+    Trilinear,
+    Anisotropic(u16),
+}
+
 pub struct TextureBuilder<'tb>{
     pub data: Option<Vec<u8>>,
     pub size: wgpu::Extent3d,
@@ -160,6 +588,8 @@ pub struct TextureBuilder<'tb>{
     pub usage: wgpu::TextureUsages,
     pub format: wgpu::TextureFormat,
     pub dimension: wgpu::TextureDimension,
+    pub mip_level_count: u32,
+    pub sample_count: u32,
     pub label: wgpu::Label<'tb>,
 }
 
@@ -180,8 +610,7 @@ impl<'tb> Default for TextureBuilder<'tb>{
                 | wgpu::TextureUsages::COPY_SRC
                 | wgpu::TextureUsages::RENDER_ATTACHMENT;
 
-        // Default format
-        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let format = default_format();
 
         let dimension = wgpu::TextureDimension::D2;
 
@@ -192,6 +621,8 @@ impl<'tb> Default for TextureBuilder<'tb>{
             usage,
             format,
             dimension,
+            mip_level_count: 1,
+            sample_count: 1,
             label: None,
         }
     }
@@ -203,12 +634,50 @@ impl<'tb> TextureBuilder<'tb>{
         Self::default()
     }
 
+    ///
+    /// Sets the number of samples of a multisampled texture.
+    ///
+    /// Multisampled textures can only be used as render attachment and bound as texture,
+    /// therefore all other usages are removed when building if count is larger than 1.
+    /// They have to be resolved to a single sampled texture to be copied.
+    ///
+    #[inline]
+    pub fn sample_count(mut self, count: u32) -> Self{
+        self.sample_count = count;
+        self
+    }
+
+    ///
+    /// The usage of the texture without the usages multisampled textures do not support.
+    ///
+    fn texture_usage(&self) -> wgpu::TextureUsages{
+        if self.sample_count > 1{
+            self.usage & (wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+        }
+        else{
+            self.usage
+        }
+    }
+
     #[inline]
     pub fn dimension(mut self, dimension: wgpu::TextureDimension) -> Self{
         self.dimension = dimension;
         self
     }
 
+    ///
+    /// Sets the number of mip levels of the texture.
+    /// Only the first level is filled with data, the others can be generated with
+    /// Texture::generate_mipmaps.
+    ///
+    /// Building panics unless the count is between 1 and max_mip_level_count of the size.
+    ///
+    #[inline]
+    pub fn mip_levels(mut self, mip_level_count: u32) -> Self{
+        self.mip_level_count = mip_level_count;
+        self
+    }
+
     #[inline]
     pub fn format(mut self, format: wgpu::TextureFormat) -> Self{
         self.format = format;
@@ -279,6 +748,27 @@ impl<'tb> TextureBuilder<'tb>{
         self
     }
 
+    ///
+    /// Sets filters and anisotropy of the sampler according to the quality.
+    ///
+    pub fn set_quality(mut self, quality: TextureQuality) -> Self{
+        let (filter, mipmap_filter, anisotropy_clamp) = match quality{
+            TextureQuality::Pixelated => (wgpu::FilterMode::Nearest, wgpu::FilterMode::Nearest, None),
+            TextureQuality::Bilinear => (wgpu::FilterMode::Linear, wgpu::FilterMode::Nearest, None),
+            TextureQuality::Trilinear => (wgpu::FilterMode::Linear, wgpu::FilterMode::Linear, None),
+            TextureQuality::Anisotropic(anisotropy) => (
+                wgpu::FilterMode::Linear,
+                wgpu::FilterMode::Linear,
+                std::num::NonZeroU8::new(anisotropy.min(u8::MAX as u16) as u8)
+            ),
+        };
+        self.sampler_descriptor.mag_filter = filter;
+        self.sampler_descriptor.min_filter = filter;
+        self.sampler_descriptor.mipmap_filter = mipmap_filter;
+        self.sampler_descriptor.anisotropy_clamp = anisotropy_clamp;
+        self
+    }
+
     #[inline]
     pub fn set_lod_min_clamp(mut self, lod_min_clamp: f32) -> Self{
         self.sampler_descriptor.lod_min_clamp = lod_min_clamp;
@@ -316,14 +806,19 @@ impl<'tb> TextureBuilder<'tb>{
         self
     }
 
-    pub fn from_image(mut self, img: &image::DynamicImage) -> Self{
+    ///
+    /// Sets the data and size from an image converted to the format of the builder.
+    ///
+    /// Returns an error if the format is not one of the Rgba8 or Bgra8 formats.
+    ///
+    pub fn from_image(mut self, img: &image::DynamicImage) -> Result<Self>{
         let img_data: Vec<u8> = match self.format{
             wgpu::TextureFormat::Rgba8Unorm     => img.flipv().to_rgba8().into_raw(),
             wgpu::TextureFormat::Rgba8UnormSrgb => img.flipv().to_rgba8().into_raw(),
             wgpu::TextureFormat::Bgra8Unorm     => img.flipv().to_bgra8().into_raw(),
             wgpu::TextureFormat::Bgra8UnormSrgb => img.flipv().to_bgra8().into_raw(),
             _ => {
-                panic!("TextureFormat not supported")
+                bail!("TextureFormat {:?} not supported for loading images", self.format)
             }
         };
         let dims = img.dimensions();
@@ -335,54 +830,143 @@ impl<'tb> TextureBuilder<'tb>{
         };
         self.data = Some(img_data);
         self.size = extent;
-        self
+        Ok(self)
     }
 
-    pub fn from_bytes(self, bytes: &[u8]) -> Self{
-        let img = image::load_from_memory(bytes).unwrap();
+    pub fn from_bytes(self, bytes: &[u8]) -> Result<Self>{
+        let img = image::load_from_memory(bytes)
+            .context("Failed to decode image")?;
         Self::from_image(self, &img)
     }
 
-    pub fn load_from_path(self, path: &std::path::Path) -> Self{
-        let buffer = fs::read(path).unwrap();
+    pub fn load_from_path(self, path: &std::path::Path) -> Result<Self>{
+        let buffer = fs::read(path)
+            .with_context(|| format!("Failed to read image {:?}", path))?;
         Self::from_bytes(self, &buffer)
     }
 
+    ///
+    /// Configures the builder for a depth texture of the given format.
+    ///
+    /// The texture can be used as a depth attachment and be sampled with the comparison sampler
+    /// that is created alongside it.
+    /// Any data set before is discarded, since depth textures are not uploaded from the cpu.
+    ///
+    pub fn depth(mut self, format: wgpu::TextureFormat) -> Self{
+        self.format = format;
+        self.usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        self.data = None;
+        self.comparison_sampler(wgpu::CompareFunction::LessEqual)
+    }
+
+    ///
+    /// Sets the address mode of the sampler on all axes.
+    ///
+    #[inline]
+    pub fn address_mode(mut self, mode: wgpu::AddressMode) -> Self{
+        self.sampler_descriptor.address_mode_u = mode;
+        self.sampler_descriptor.address_mode_v = mode;
+        self.sampler_descriptor.address_mode_w = mode;
+        self
+    }
+
+    #[inline]
+    pub fn mag_filter(mut self, filter: wgpu::FilterMode) -> Self{
+        self.sampler_descriptor.mag_filter = filter;
+        self
+    }
+
+    #[inline]
+    pub fn min_filter(mut self, filter: wgpu::FilterMode) -> Self{
+        self.sampler_descriptor.min_filter = filter;
+        self
+    }
+
+    #[inline]
+    pub fn mipmap_filter(mut self, filter: wgpu::FilterMode) -> Self{
+        self.sampler_descriptor.mipmap_filter = filter;
+        self
+    }
+
+    ///
+    /// Makes the sampler of the texture a comparison sampler using func, for example to sample
+    /// shadow maps with hardware PCF.
+    ///
+    /// Textures with a comparison sampler have to be bound through DepthTexture, whose bindings
+    /// use wgsl::sampler_comparison.
+    ///
+    #[inline]
+    pub fn comparison_sampler(mut self, func: wgpu::CompareFunction) -> Self{
+        self.sampler_descriptor.compare = Some(func);
+        self
+    }
+
     pub fn clear<Z: IntoExtent3D>(mut self, size: Z) -> Self{
         self.size = size.into_extent_3d();
         self.data = None;
         self
     }
 
+    fn assert_size(&self){
+        assert!(
+            self.size.width > 0 && self.size.height > 0 && self.size.depth_or_array_layers > 0,
+            "Cannot create a Texture with a zero sized extent {:?} (label: {:?})",
+            self.size, self.label
+        );
+        let max_mip_level_count = match self.dimension{
+            wgpu::TextureDimension::D3 => max_mip_level_count(self.size.width.max(self.size.height), self.size.depth_or_array_layers),
+            _ => max_mip_level_count(self.size.width, self.size.height),
+        };
+        assert!(
+            self.mip_level_count > 0 && self.mip_level_count <= max_mip_level_count,
+            "A Texture of size {:?} can have 1 to {} mip levels, not {} (label: {:?})",
+            self.size, max_mip_level_count, self.mip_level_count, self.label
+        );
+    }
+
     pub fn label(mut self, label: wgpu::Label<'tb>) -> Self{
         self.label = label;
         self
     }
 
+    ///
+    /// Builds the Texture and uploads the data if provided.
+    ///
+    /// Panics if any dimension of the size is zero or if the length of the data does not match
+    /// the size and the block size of the format.
+    ///
     pub fn build(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> Texture{
+        self.assert_size();
         let texture = device.create_texture(
             &wgpu::TextureDescriptor{
                 label: self.label,
                 size: self.size,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
+                mip_level_count: self.mip_level_count,
+                sample_count: self.sample_count,
+                dimension: self.dimension,
                 format: self.format,
-                usage: self.usage
+                usage: self.texture_usage(),
             }
         );
-        /*
         let texture_view_desc = wgpu::TextureViewDescriptor{
             format: Some(self.format),
             ..Default::default()
         };
         let view = texture.create_view(&texture_view_desc);
-        */
         let sampler = device.create_sampler(
             &self.sampler_descriptor
         );
 
         if let Some(data) = &self.data{
+            let (_, block_height) = block_dimensions(self.format);
+            let rows = (self.size.height + block_height - 1) / block_height;
+            let bytes_per_row = bytes_per_row(self.format, self.size.width);
+            assert_eq!(
+                data.len(), (bytes_per_row * rows * self.size.depth_or_array_layers) as usize,
+                "Length of the data does not match the size {:?} and format {:?} (label: {:?})",
+                self.size, self.format, self.label
+            );
+
             queue.write_texture(
                 wgpu::ImageCopyTexture{
                     aspect: wgpu::TextureAspect::All,
@@ -393,8 +977,8 @@ impl<'tb> TextureBuilder<'tb>{
                 data,
                 wgpu::ImageDataLayout{
                     offset: 0,
-                    bytes_per_row: std::num::NonZeroU32::new(4 * self.size.width),
-                    rows_per_image: std::num::NonZeroU32::new(self.size.height),
+                    bytes_per_row: std::num::NonZeroU32::new(bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(rows),
                 },
                 self.size,
             );
@@ -402,23 +986,32 @@ impl<'tb> TextureBuilder<'tb>{
 
         Texture{
             texture,
-            //view,
+            view,
             sampler,
             format: self.format,
             size: self.size,
+            mip_level_count: self.mip_level_count,
+            dimension: self.dimension,
+            usage: self.texture_usage(),
         }
     }
 
+    ///
+    /// Builds the Texture without uploading any data.
+    ///
+    /// Panics if any dimension of the size is zero.
+    ///
     pub fn build_empty(&mut self, device: &wgpu::Device) -> Texture{
+        self.assert_size();
         let texture = device.create_texture(
             &wgpu::TextureDescriptor{
                 label: self.label,
                 size: self.size,
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
+                mip_level_count: self.mip_level_count,
+                sample_count: self.sample_count,
+                dimension: self.dimension,
                 format: self.format,
-                usage: self.usage
+                usage: self.texture_usage(),
             }
         );
         let texture_view_desc = wgpu::TextureViewDescriptor{
@@ -432,10 +1025,13 @@ impl<'tb> TextureBuilder<'tb>{
 
         Texture{
             texture,
-            //view,
+            view,
             sampler,
             format: self.format,
             size: self.size,
+            mip_level_count: self.mip_level_count,
+            dimension: self.dimension,
+            usage: self.texture_usage(),
         }
     }
 
@@ -446,7 +1042,697 @@ impl<'tb> TextureBuilder<'tb>{
 }
 
 impl Texture{
-    pub fn slice<S: RangeBounds<u32>>(&self, bound_x: S, bound_y: S, bound_z: S) -> TextureSlice{
+    ///
+    /// Loads an image as Rgba8UnormSrgb texture.
+    ///
+    /// Should be used for color data such as albedo maps, which are stored in sRGB.
+    ///
+    pub fn load_srgb(device: &wgpu::Device, queue: &wgpu::Queue, path: &std::path::Path, label: wgpu::Label) -> Result<Self>{
+        Ok(TextureBuilder::new()
+            .format(wgpu::TextureFormat::Rgba8UnormSrgb)
+            .label(label)
+            .load_from_path(path)?
+            .build(device, queue))
+    }
+
+    ///
+    /// Loads an image as Rgba8Unorm texture.
+    ///
+    /// Should be used for non color data such as normal or roughness maps.
+    ///
+    pub fn load_linear(device: &wgpu::Device, queue: &wgpu::Queue, path: &std::path::Path, label: wgpu::Label) -> Result<Self>{
+        Ok(TextureBuilder::new()
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .label(label)
+            .load_from_path(path)?
+            .build(device, queue))
+    }
+
+    ///
+    /// Decodes an encoded image on a separate thread and uploads it as a texture of format once
+    /// decoding has finished.
+    ///
+    /// In contrast to TextureBuilder::from_bytes this does not block the calling thread while
+    /// decoding, which makes it suitable for streaming textures in the background.
+    /// The format has to be one of the formats supported by TextureBuilder::from_image.
+    ///
+    pub async fn from_bytes_async(device: &wgpu::Device, queue: &wgpu::Queue, bytes: Vec<u8>, label: wgpu::Label<'_>, format: wgpu::TextureFormat) -> Result<Self>{
+        let builder = utils::spawn_blocking(move ||{
+            TextureBuilder::new()
+                .format(format)
+                .from_bytes(&bytes)
+        }).await?;
+
+        Ok(builder
+            .label(label)
+            .build(device, queue))
+    }
+
+    ///
+    /// Reads and decodes an image file on a separate thread and uploads it as a texture of format
+    /// once decoding has finished (see from_bytes_async).
+    ///
+    pub async fn load_from_path_async(device: &wgpu::Device, queue: &wgpu::Queue, path: std::path::PathBuf, label: wgpu::Label<'_>, format: wgpu::TextureFormat) -> Result<Self>{
+        let builder = utils::spawn_blocking(move ||{
+            TextureBuilder::new()
+                .format(format)
+                .load_from_path(&path)
+        }).await?;
+
+        Ok(builder
+            .label(label)
+            .build(device, queue))
+    }
+
+    ///
+    /// Loads a 2D texture from a KTX2 container uploading every mip level without decoding it.
+    ///
+    /// Supported are the block compressed formats BC1 to BC7, which require the device to be
+    /// created with the TEXTURE_COMPRESSION_BC feature, as well as Rgba8Unorm(Srgb).
+    /// Returns an error for supercompressed files, array textures, cube maps and 3D textures.
+    ///
+    pub fn load_ktx2(device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8], label: wgpu::Label) -> Result<Self>{
+        const IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+
+        let read_u32 = |offset: usize| -> Result<u32>{
+            let word = bytes.get(offset..offset + 4).ok_or(anyhow!("KTX2 file is truncated"))?;
+            Ok(u32::from_le_bytes(word.try_into().unwrap()))
+        };
+        let read_u64 = |offset: usize| -> Result<u64>{
+            let word = bytes.get(offset..offset + 8).ok_or(anyhow!("KTX2 file is truncated"))?;
+            Ok(u64::from_le_bytes(word.try_into().unwrap()))
+        };
+
+        ensure!(bytes.starts_with(&IDENTIFIER), "Not a KTX2 file");
+
+        let format = ktx2_format(read_u32(12)?)?;
+        let width = read_u32(20)?;
+        let height = read_u32(24)?;
+        let depth = read_u32(28)?;
+        let layer_count = read_u32(32)?;
+        let face_count = read_u32(36)?;
+        let level_count = read_u32(40)?.max(1);
+        let supercompression = read_u32(44)?;
+
+        ensure!(supercompression == 0, "Supercompressed KTX2 files are not supported");
+        ensure!(
+            height > 0 && depth == 0 && layer_count == 0 && face_count == 1,
+            "Only 2D KTX2 textures are supported"
+        );
+        ensure!(width > 0, "KTX2 texture has a width of 0");
+        let max_dimension = device.limits().max_texture_dimension_2d;
+        ensure!(
+            width <= max_dimension && height <= max_dimension,
+            "KTX2 texture of size {}x{} exceeds the maximum texture dimension {}", width, height, max_dimension
+        );
+        let max_level_count = max_mip_level_count(width, height);
+        ensure!(
+            level_count <= max_level_count,
+            "KTX2 texture of size {}x{} can not have {} mip levels", width, height, level_count
+        );
+        let (block_width, block_height) = block_dimensions(format);
+        ensure!(
+            width % block_width == 0 && height % block_height == 0,
+            "KTX2 texture of size {}x{} is not a multiple of the block size {}x{} of {:?}",
+            width, height, block_width, block_height, format
+        );
+        let required_features = format.describe().required_features;
+        ensure!(
+            device.features().contains(required_features),
+            "Format {:?} requires the features {:?}", format, required_features
+        );
+
+        let texture = TextureBuilder::new()
+            .clear([width, height])
+            .format(format)
+            .mip_levels(level_count)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC)
+            .label(label)
+            .build_empty(device);
+
+        for level in 0..level_count{
+            let index = 80 + level as usize * 24;
+            let offset = read_u64(index)?;
+            let length = read_u64(index + 8)?;
+            let end = match offset.checked_add(length){
+                Some(end) if end <= bytes.len() as u64 => end,
+                _ => bail!("Mip level {} lies outside of the KTX2 file", level),
+            };
+            let data = &bytes[offset as usize..end as usize];
+
+            let level_width = (width >> level).max(1);
+            let level_height = (height >> level).max(1);
+            let rows = (level_height + block_height - 1) / block_height;
+            ensure!(
+                data.len() == (bytes_per_row(format, level_width) * rows) as usize,
+                "Mip level {} has an unexpected length of {} bytes", level, data.len()
+            );
+
+            queue.write_texture(
+                wgpu::ImageCopyTexture{
+                    aspect: wgpu::TextureAspect::All,
+                    texture: &texture.texture,
+                    mip_level: level,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                data,
+                wgpu::ImageDataLayout{
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(bytes_per_row(format, level_width)),
+                    rows_per_image: std::num::NonZeroU32::new(rows),
+                },
+                wgpu::Extent3d{
+                    width: level_width.align_ceil(block_width),
+                    height: level_height.align_ceil(block_height),
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        Ok(texture)
+    }
+
+    ///
+    /// Creates a 3D Texture from tightly packed data.
+    /// The data is uploaded slice by slice with extent.height rows per slice.
+    ///
+    pub fn new_3d(device: &wgpu::Device, queue: &wgpu::Queue, data: Vec<u8>, extent: wgpu::Extent3d, format: wgpu::TextureFormat, label: wgpu::Label) -> Self{
+        TextureBuilder::new()
+            .format(format)
+            .dimension(wgpu::TextureDimension::D3)
+            .from_raw(data, extent)
+            .label(label)
+            .build(device, queue)
+    }
+
+    ///
+    /// Creates an empty Texture that can be written to as storage texture and sampled in a later
+    /// pass.
+    ///
+    /// The texture can be bound as storage texture by wrapping it in a StorageTexture and as
+    /// sampled texture using create_bind_group_at.
+    /// Returns an error if the format does not support the STORAGE_BINDING usage.
+    ///
+    pub fn new_storage<Z: IntoExtent3D>(device: &wgpu::Device, size: Z, format: wgpu::TextureFormat, label: wgpu::Label) -> Result<Self>{
+        let allowed_usages = format.describe().guaranteed_format_features.allowed_usages;
+        ensure!(
+            allowed_usages.contains(wgpu::TextureUsages::STORAGE_BINDING),
+            "Format {:?} does not support the STORAGE_BINDING usage", format
+        );
+
+        Ok(TextureBuilder::new()
+            .clear(size)
+            .format(format)
+            .usage(wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC)
+            .label(label)
+            .build_empty(device))
+    }
+
+    ///
+    /// Generates the BRDF integration lookup table used for image based lighting.
+    ///
+    /// The returned Rg16Float texture is indexed by dot(n, v) in x and the roughness in y and
+    /// contains the scale and bias to F0 of the split sum approximation.
+    ///
+    pub fn brdf_lut(device: &wgpu::Device, queue: &wgpu::Queue, size: u32) -> Self{
+        let lut = TextureBuilder::new()
+            .clear([size, size])
+            .format(wgpu::TextureFormat::Rg16Float)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC)
+            .label(Some("brdf_lut"))
+            .build_empty(device);
+
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+            label: Some("brdf_lut_shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(format!("{}{}", IBL_COMMON_SHADER, BRDF_LUT_SHADER))),
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor{
+            label: Some("brdf_lut_pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState{
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState{
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[lut.format.into()],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let view = lut.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some("brdf_lut")});
+        {
+            let mut rpass = RenderPassBuilder::new()
+                .push_color_attachment(view.color_attachment_clear())
+                .begin(&mut encoder, Some("brdf_lut_pass"));
+
+            rpass.render_pass.set_pipeline(&pipeline);
+            rpass.render_pass.draw(0..3, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+
+        lut
+    }
+
+    ///
+    /// Prefilters an environment cube map for specular image based lighting.
+    ///
+    /// env has to be a cube map (a texture with 6 array layers) of a renderable format.
+    /// Every mip level of the returned cube map holds the environment convolved with the GGX
+    /// distribution, the roughness increases linearly from 0 at the first to 1 at the last mip
+    /// level.
+    ///
+    /// The returned texture has 6 layers, its view and bindings are 2D arrays. Use cube_view to
+    /// sample it as a cube map.
+    ///
+    /// Panics if mip_levels is 0 or exceeds max_mip_level_count of the size of env.
+    ///
+    pub fn prefilter_env(device: &wgpu::Device, queue: &wgpu::Queue, env: &Texture, mip_levels: u32) -> Self{
+        assert_eq!(env.size.depth_or_array_layers, 6, "The environment has to be a cube map with 6 layers");
+        let max_mip_levels = max_mip_level_count(env.size.width, env.size.height);
+        assert!(
+            mip_levels > 0 && mip_levels <= max_mip_levels,
+            "An environment of size {}x{} can be prefiltered into 1 to {} mip levels, not {}",
+            env.size.width, env.size.height, max_mip_levels, mip_levels
+        );
+
+        let prefiltered = TextureBuilder::new()
+            .clear(env.size)
+            .format(env.format)
+            .mip_levels(mip_levels)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC)
+            .label(Some("prefiltered_env"))
+            .build_empty(device);
+
+        Self::render_env_cube(device, queue, env, &prefiltered, PREFILTER_ENV_SHADER, "prefilter_env");
+
+        prefiltered
+    }
+
+    ///
+    /// Convolves an environment cube map to a cube map of the diffuse irradiance with size x size
+    /// texels per face.
+    ///
+    /// env has to be a cube map (a texture with 6 array layers) of a renderable format.
+    /// The irradiance is already divided by PI, so it only has to be multiplied with the albedo
+    /// to get the diffuse lighting.
+    ///
+    /// Like prefilter_env this returns a texture with 6 layers, use cube_view to sample it.
+    ///
+    pub fn irradiance_map(device: &wgpu::Device, queue: &wgpu::Queue, env: &Texture, size: u32) -> Self{
+        assert_eq!(env.size.depth_or_array_layers, 6, "The environment has to be a cube map with 6 layers");
+
+        let irradiance = TextureBuilder::new()
+            .clear([size, size, 6])
+            .format(env.format)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC)
+            .label(Some("irradiance_map"))
+            .build_empty(device);
+
+        Self::render_env_cube(device, queue, env, &irradiance, IRRADIANCE_SHADER, "irradiance_map");
+
+        irradiance
+    }
+
+    ///
+    /// Renders every face and mip level of the cube map dst with a fragment shader appended to
+    /// IBL_COMMON_SHADER and IBL_CUBE_SHADER, sampling from the cube map env.
+    /// The roughness passed to the shader increases linearly from 0 at the first to 1 at the last
+    /// mip level.
+    ///
+    fn render_env_cube(device: &wgpu::Device, queue: &wgpu::Queue, env: &Texture, dst: &Texture, fs_src: &str, label: &str){
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Owned(format!("{}{}{}", IBL_COMMON_SHADER, IBL_CUBE_SHADER, fs_src))),
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor{
+            label: Some(label),
+            layout: None,
+            vertex: wgpu::VertexState{
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState{
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[dst.format.into()],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor{
+            label: Some(label),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let env_view = env.cube_view();
+
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: Some(label)});
+        let mip_levels = dst.mip_level_count;
+        for mip in 0..mip_levels{
+            let roughness = if mip_levels > 1 {mip as f32 / (mip_levels - 1) as f32} else {0.0};
+
+            for face in 0..6{
+                let params = BufferBuilder::new()
+                    .uniform()
+                    .build(device, &[PrefilterParams{
+                        face,
+                        roughness,
+                        _pad: [0.0; 2],
+                    }]);
+
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
+                    label: None,
+                    layout: &bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry{
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&env_view),
+                        },
+                        wgpu::BindGroupEntry{
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&sampler),
+                        },
+                        wgpu::BindGroupEntry{
+                            binding: 2,
+                            resource: params.as_entire_binding(),
+                        },
+                    ],
+                });
+
+                let view = dst.texture.create_view(&wgpu::TextureViewDescriptor{
+                    label: Some(label),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_mip_level: mip,
+                    mip_level_count: std::num::NonZeroU32::new(1),
+                    base_array_layer: face,
+                    array_layer_count: std::num::NonZeroU32::new(1),
+                    ..Default::default()
+                });
+
+                let mut rpass = RenderPassBuilder::new()
+                    .push_color_attachment(view.color_attachment_clear())
+                    .begin(&mut encoder, Some(label));
+
+                rpass.render_pass.set_pipeline(&pipeline);
+                rpass.render_pass.set_bind_group(0, &bind_group, &[]);
+                rpass.render_pass.draw(0..3, 0..1);
+            }
+        }
+        queue.submit(Some(encoder.finish()));
+    }
+
+    ///
+    /// Returns the layout entries of a texture view and the sampler of this texture at explicit
+    /// binding indices for shaders whose bindings are not contiguous.
+    ///
+    pub fn entries_at(texture_binding: u32, sampler_binding: u32, visibility: Option<wgpu::ShaderStages>) -> [wgpu::BindGroupLayoutEntry; 2]{
+        let visibility = visibility.unwrap_or(wgpu::ShaderStages::all());
+        [
+            wgpu::BindGroupLayoutEntry{
+                binding: texture_binding,
+                visibility,
+                ty: binding::wgsl::texture_2d(),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry{
+                binding: sampler_binding,
+                visibility,
+                ty: binding::wgsl::sampler(),
+                count: None,
+            },
+        ]
+    }
+
+    ///
+    /// Returns the entries of the view and the sampler of this texture at explicit binding
+    /// indices (see entries_at).
+    ///
+    pub fn resources_at<'r>(&'r self, view: &'r wgpu::TextureView, texture_binding: u32, sampler_binding: u32) -> [wgpu::BindGroupEntry<'r>; 2]{
+        [
+            wgpu::BindGroupEntry{
+                binding: texture_binding,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry{
+                binding: sampler_binding,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            },
+        ]
+    }
+
+    ///
+    /// Creates a BindGroup and its layout binding the view and the sampler of this texture at
+    /// explicit binding indices.
+    ///
+    pub fn create_bind_group_at(&self, device: &wgpu::Device, view: &wgpu::TextureView, texture_binding: u32, sampler_binding: u32, visibility: Option<wgpu::ShaderStages>) -> (BindGroupLayoutWithDesc, wgpu::BindGroup){
+        let entries = Self::entries_at(texture_binding, sampler_binding, visibility);
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor{
+            label: None,
+            entries: &entries,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
+            label: None,
+            layout: &layout,
+            entries: &self.resources_at(view, texture_binding, sampler_binding),
+        });
+
+        (
+            BindGroupLayoutWithDesc{
+                layout,
+                entries: Vec::from(entries),
+            },
+            bind_group,
+        )
+    }
+
+    ///
+    /// Creates a 2D view of this texture that binds only the view and not the sampler of the
+    /// texture, so that it can be combined with a shared Sampler.
+    ///
+    pub fn texture_view(&self) -> TextureView{
+        TextureView{
+            view: self.texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            dimension: wgpu::TextureViewDimension::D2,
+        }
+    }
+
+    ///
+    /// Clears all mip levels and layers of the texture to color.
+    /// Depth textures are cleared to the depth color.r and their stencil to 0.
+    ///
+    /// Clearing a color texture to zero (wgpu::Color::TRANSPARENT) uses encoder.clear_texture if
+    /// the device has the CLEAR_COMMANDS feature and the texture the COPY_DST usage.
+    /// Otherwise every mip level and layer is cleared with a render pass, which requires the
+    /// RENDER_ATTACHMENT usage and returns an error for 3D textures.
+    ///
+    pub fn clear(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, color: wgpu::Color) -> Result<()>{
+        let depth = self.format.describe().sample_type == wgpu::TextureSampleType::Depth;
+
+        if color == wgpu::Color::TRANSPARENT
+            && !depth
+            && device.features().contains(wgpu::Features::CLEAR_COMMANDS)
+            && self.usage.contains(wgpu::TextureUsages::COPY_DST){
+            encoder.clear_texture(&self.texture, &wgpu::ImageSubresourceRange::default());
+            return Ok(());
+        }
+
+        ensure!(
+            self.dimension == wgpu::TextureDimension::D2,
+            "Only 2D textures can be cleared with a render pass, the texture is {:?}", self.dimension
+        );
+        ensure!(
+            self.usage.contains(wgpu::TextureUsages::RENDER_ATTACHMENT),
+            "Clearing a texture with a render pass requires the RENDER_ATTACHMENT usage"
+        );
+
+        let stencil = self.format == wgpu::TextureFormat::Depth24PlusStencil8;
+
+        for mip_level in 0..self.mip_level_count{
+            for layer in 0..self.size.depth_or_array_layers{
+                let view = self.texture.create_view(&wgpu::TextureViewDescriptor{
+                    label: Some("clear_view"),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_mip_level: mip_level,
+                    mip_level_count: std::num::NonZeroU32::new(1),
+                    base_array_layer: layer,
+                    array_layer_count: std::num::NonZeroU32::new(1),
+                    ..Default::default()
+                });
+                if depth{
+                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor{
+                        label: Some("clear_pass"),
+                        color_attachments: &[],
+                        depth_stencil_attachment: Some(view.depth_attachment_clear_with(
+                            color.r as f32,
+                            stencil.then(|| wgpu::Operations{
+                                load: wgpu::LoadOp::Clear(0),
+                                store: true,
+                            }),
+                        )),
+                    });
+                }
+                else{
+                    encoder.begin_render_pass(&wgpu::RenderPassDescriptor{
+                        label: Some("clear_pass"),
+                        color_attachments: &[view.color_attachment_clear_with(color)],
+                        depth_stencil_attachment: None,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Creates a Cube view of all mip levels that can be bound as texture_cube.
+    ///
+    /// Panics if the texture does not have 6 layers.
+    ///
+    pub fn cube_view(&self) -> wgpu::TextureView{
+        assert_eq!(self.size.depth_or_array_layers, 6, "A cube view requires a texture with 6 layers");
+        self.texture.create_view(&wgpu::TextureViewDescriptor{
+            label: Some("cube_view"),
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Creates a D2Array view of all layers that can be used as attachment of a multiview render
+    /// pass (see RenderPipelineBuilder::set_multiview).
+    ///
+    pub fn multiview_view(&self) -> wgpu::TextureView{
+        self.texture.create_view(&wgpu::TextureViewDescriptor{
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            array_layer_count: std::num::NonZeroU32::new(self.size.depth_or_array_layers),
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Creates a view of the depth aspect of this texture.
+    ///
+    pub fn depth_view(&self) -> wgpu::TextureView{
+        self.texture.create_view(&wgpu::TextureViewDescriptor{
+            format: Some(self.format),
+            aspect: wgpu::TextureAspect::DepthOnly,
+            ..Default::default()
+        })
+    }
+
+    ///
+    /// Generates the mip levels 1.. by successively downsampling the previous level.
+    /// Every layer of array and cube textures is downsampled separately.
+    ///
+    /// The texture has to have the RENDER_ATTACHMENT usage and a format that can be rendered to.
+    ///
+    pub fn generate_mipmaps(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder){
+        let pipeline = blit_pipeline(device, self.format, "mipmap");
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor{
+            label: Some("mipmap_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group_layout = pipeline.get_bind_group_layout(0);
+
+        for layer in 0..self.size.depth_or_array_layers{
+            let views: Vec<wgpu::TextureView> = (0..self.mip_level_count).map(|mip|{
+                self.texture.create_view(&wgpu::TextureViewDescriptor{
+                    label: Some("mipmap_view"),
+                    format: Some(self.format),
+                    dimension: Some(wgpu::TextureViewDimension::D2),
+                    base_mip_level: mip,
+                    mip_level_count: std::num::NonZeroU32::new(1),
+                    base_array_layer: layer,
+                    array_layer_count: std::num::NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            }).collect();
+
+            for mip in 1..self.mip_level_count as usize{
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor{
+                    label: None,
+                    layout: &bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry{
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&views[mip - 1]),
+                        },
+                        wgpu::BindGroupEntry{
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&sampler),
+                        },
+                    ],
+                });
+
+                let mut rpass = RenderPassBuilder::new()
+                    .push_color_attachment(views[mip].color_attachment_clear())
+                    .begin(encoder, Some("mipmap_pass"));
+
+                rpass.render_pass.set_pipeline(&pipeline);
+                rpass.render_pass.set_bind_group(0, &bind_group, &[]);
+                rpass.render_pass.draw(0..3, 0..1);
+            }
+        }
+    }
+
+    ///
+    /// Reads the whole texture back to the cpu (see TextureSlice::read_to_vec).
+    ///
+    pub async fn read_to_vec(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> Result<Vec<u8>>{
+        self.slice(.., .., ..).read_to_vec(device, queue).await
+    }
+
+    ///
+    /// Reads the texture back and saves it as a png.
+    ///
+    /// The image is flipped vertically to undo the flip done when loading images.
+    /// Only Rgba8 and Bgra8 formats are supported.
+    ///
+    pub fn save_to_png(&self, device: &wgpu::Device, queue: &wgpu::Queue, path: &str) -> Result<()>{
+        let mut data = pollster::block_on(self.read_to_vec(device, queue))?;
+
+        match self.format{
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => {},
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                for pixel in data.chunks_exact_mut(4){
+                    pixel.swap(0, 2);
+                }
+            },
+            _ => bail!("TextureFormat {:?} not supported", self.format),
+        }
+
+        let img = image::RgbaImage::from_raw(self.size.width, self.size.height, data)
+            .ok_or_else(|| anyhow!("Texture data does not fit the image size"))?;
+
+        image::imageops::flip_vertical(&img).save_with_format(path, image::ImageFormat::Png)?;
+        Ok(())
+    }
+
+    pub fn slice<SX: RangeBounds<u32>, SY: RangeBounds<u32>, SZ: RangeBounds<u32>>(&self, bound_x: SX, bound_y: SY, bound_z: SZ) -> TextureSlice{
         let range_x = bound_x.clamp(0..self.size.width);
         let range_y = bound_y.clamp(0..self.size.height);
         let range_z = bound_z.clamp(0..self.size.depth_or_array_layers);
@@ -527,6 +1813,342 @@ impl BindGroupContent for TextureView{
     }
 }
 
+///
+/// A 2D array texture with a D2Array view of all its layers, for example for texture atlases or
+/// shadow cascades.
+///
+/// Binds the view as texture_2d_array followed by the sampler of the texture.
+///
+pub struct TextureArray{
+    pub texture: Texture,
+    pub view: wgpu::TextureView,
+}
+
+impl TextureArray{
+    ///
+    /// Creates a TextureArray from a 2D texture with texture.size.depth_or_array_layers layers.
+    ///
+    pub fn new(texture: Texture) -> Self{
+        let view = texture.texture.create_view(&wgpu::TextureViewDescriptor{
+            label: None,
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        Self{
+            texture,
+            view,
+        }
+    }
+
+    #[inline]
+    pub fn layers(&self) -> u32{
+        self.texture.size.depth_or_array_layers
+    }
+}
+
+impl BindGroupContent for TextureArray{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry>{
+        let visibility = visibility.unwrap_or(wgpu::ShaderStages::all());
+        vec![
+            BindGroupLayoutEntry::new(visibility, binding::wgsl::texture_2d_array()),
+            BindGroupLayoutEntry::new(visibility, binding::wgsl::sampler()),
+        ]
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource>{
+        vec![
+            wgpu::BindingResource::TextureView(&self.view),
+            wgpu::BindingResource::Sampler(&self.texture.sampler),
+        ]
+    }
+}
+
+///
+/// Creates a pipeline drawing a fullscreen triangle with BLIT_SHADER to a target of format.
+///
+fn blit_pipeline(device: &wgpu::Device, format: wgpu::TextureFormat, label: &str) -> wgpu::RenderPipeline{
+    let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor{
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(BLIT_SHADER)),
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor{
+        label: Some(label),
+        layout: None,
+        vertex: wgpu::VertexState{
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState{
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[format.into()],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+    })
+}
+
+///
+/// Binds the first mip level of src and its sampler for a pipeline created with blit_pipeline.
+///
+fn blit_bind_group(device: &wgpu::Device, pipeline: &wgpu::RenderPipeline, src: &Texture) -> wgpu::BindGroup{
+    let src_view = src.texture.create_view(&wgpu::TextureViewDescriptor{
+        mip_level_count: std::num::NonZeroU32::new(1),
+        ..Default::default()
+    });
+
+    device.create_bind_group(&wgpu::BindGroupDescriptor{
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry{
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&src_view),
+            },
+            wgpu::BindGroupEntry{
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&src.sampler),
+            },
+        ],
+    })
+}
+
+///
+/// Copies textures to targets of a fixed format with a fullscreen triangle, sampling the source
+/// with its sampler.
+///
+/// In contrast to a texture copy the source and target can differ in size and format, which
+/// makes this the basic building block of post processing passes.
+///
+pub struct Blit{
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl Blit{
+    ///
+    /// Creates a Blit that can draw to targets with the given format.
+    ///
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self{
+        Self{
+            pipeline: blit_pipeline(device, format, "blit"),
+        }
+    }
+
+    ///
+    /// Draws src stretched over the whole of dst.
+    ///
+    pub fn blit(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, src: &Texture, dst: &wgpu::TextureView){
+        let bind_group = blit_bind_group(device, &self.pipeline, src);
+
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(dst.color_attachment_load())
+            .begin(encoder, Some("blit_pass"));
+
+        rpass.render_pass.set_pipeline(&self.pipeline);
+        rpass.render_pass.set_bind_group(0, &bind_group, &[]);
+        rpass.render_pass.draw(0..3, 0..1);
+    }
+}
+
+///
+/// Draws textures to a target while preserving their aspect ratio.
+///
+/// The texture is fitted into the target and the remaining area is cleared to black.
+///
+pub struct TextureViewer{
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl TextureViewer{
+    ///
+    /// Creates a TextureViewer that can draw to targets with the given format.
+    ///
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self{
+        let pipeline = blit_pipeline(device, format, "texture_viewer");
+
+        Self{
+            pipeline,
+        }
+    }
+
+    ///
+    /// Computes the viewport (x, y, width, height) that fits a texture of size src into a target
+    /// of size dst while preserving its aspect ratio.
+    ///
+    pub fn fit_viewport(src: [u32; 2], dst: [u32; 2]) -> [f32; 4]{
+        let src_aspect = src[0] as f32 / src[1] as f32;
+        let dst_aspect = dst[0] as f32 / dst[1] as f32;
+
+        if src_aspect > dst_aspect{
+            let height = dst[0] as f32 / src_aspect;
+            [0.0, (dst[1] as f32 - height) / 2.0, dst[0] as f32, height]
+        }
+        else{
+            let width = dst[1] as f32 * src_aspect;
+            [(dst[0] as f32 - width) / 2.0, 0.0, width, dst[1] as f32]
+        }
+    }
+
+    ///
+    /// Draws src letterboxed to the target.
+    ///
+    pub fn draw(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, src: &Texture, target: &wgpu::TextureView, target_size: [u32; 2]){
+        let bind_group = blit_bind_group(device, &self.pipeline, src);
+
+        let [x, y, width, height] = Self::fit_viewport([src.size.width, src.size.height], target_size);
+
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(target.color_attachment_clear_with(wgpu::Color::BLACK))
+            .begin(encoder, Some("texture_viewer_pass"));
+
+        rpass.render_pass.set_pipeline(&self.pipeline);
+        rpass.render_pass.set_bind_group(0, &bind_group, &[]);
+        rpass.render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+        rpass.render_pass.draw(0..3, 0..1);
+    }
+}
+
+///
+/// A depth Texture that can be bound as a shadow map.
+///
+/// Binds a depth texture view together with the comparison sampler of the Texture
+/// (see TextureBuilder::depth).
+///
+#[derive(DerefMut)]
+pub struct DepthTexture{
+    #[target]
+    pub texture: Texture,
+    pub view: wgpu::TextureView,
+}
+
+impl DepthTexture{
+    pub fn new(texture: Texture) -> Self{
+        let view = texture.depth_view();
+        Self{
+            texture,
+            view,
+        }
+    }
+}
+
+impl BindGroupContent for DepthTexture{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        vec![
+            BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::texture_depth_2d()),
+            BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::sampler_comparison()),
+        ]
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource> {
+        vec![
+            wgpu::BindingResource::TextureView(&self.view),
+            wgpu::BindingResource::Sampler(&self.texture.sampler),
+        ]
+    }
+}
+
+///
+/// Marker types for the formats a StorageTexture can have.
+///
+pub mod storage_format{
+    pub trait StorageTextureFormat{
+        const FORMAT: wgpu::TextureFormat;
+    }
+
+    macro_rules! storage_texture_format{
+        ($($name:ident)+) => {
+            $(
+                pub struct $name;
+                impl StorageTextureFormat for $name{
+                    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::$name;
+                }
+            )+
+        }
+    }
+
+    storage_texture_format!(
+        Rgba8Unorm Rgba8Snorm Rgba8Uint Rgba8Sint
+        Rgba16Uint Rgba16Sint Rgba16Float
+        R32Uint R32Sint R32Float
+        Rg32Uint Rg32Sint Rg32Float
+        Rgba32Uint Rgba32Sint Rgba32Float
+    );
+}
+
+///
+/// Marker types for the access a shader has to a StorageTexture.
+///
+pub mod storage_access{
+    pub trait StorageTextureAccess{
+        const ACCESS: wgpu::StorageTextureAccess;
+    }
+
+    pub struct ReadOnly;
+    impl StorageTextureAccess for ReadOnly{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::ReadOnly;
+    }
+
+    pub struct WriteOnly;
+    impl StorageTextureAccess for WriteOnly{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::WriteOnly;
+    }
+
+    pub struct ReadWrite;
+    impl StorageTextureAccess for ReadWrite{
+        const ACCESS: wgpu::StorageTextureAccess = wgpu::StorageTextureAccess::ReadWrite;
+    }
+}
+
+///
+/// A Texture that is bound as a storage texture.
+///
+/// The format and access are part of the type since they are part of the BindGroupLayout:
+/// ```ignore
+/// let storage = StorageTexture::<storage_format::Rgba8Unorm, storage_access::WriteOnly>::new(texture);
+/// ```
+/// The Texture has to have the STORAGE_BINDING usage.
+///
+#[derive(DerefMut)]
+pub struct StorageTexture<F: StorageTextureFormat, A: StorageTextureAccess>{
+    #[target]
+    pub texture: Texture,
+    pub view: wgpu::TextureView,
+    _ty: PhantomData<(F, A)>,
+}
+
+impl<F: StorageTextureFormat, A: StorageTextureAccess> StorageTexture<F, A>{
+    pub fn new(texture: Texture) -> Self{
+        assert_eq!(texture.format, F::FORMAT, "Texture format does not match the StorageTexture format");
+        let view = texture.texture.create_view(&wgpu::TextureViewDescriptor{
+            format: Some(texture.format),
+            ..Default::default()
+        });
+        Self{
+            texture,
+            view,
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<F: StorageTextureFormat, A: StorageTextureAccess> BindGroupContent for StorageTexture<F, A>{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<BindGroupLayoutEntry> {
+        vec![
+            BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::texture_storage_2d(F::FORMAT, A::ACCESS)),
+        ]
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource> {
+        vec![
+            wgpu::BindingResource::TextureView(&self.view),
+        ]
+    }
+}
+
 #[cfg(feature = "imgui")]
 impl ColorAttachment for imgui_wgpu::Texture{
     fn color_attachment_clear(&self) -> wgpu::RenderPassColorAttachment {
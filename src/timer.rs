@@ -0,0 +1,302 @@
+use crate::*;
+use std::future::Future;
+use std::pin::Pin;
+
+type MapFuture = Pin<Box<dyn Future<Output = Result<(), wgpu::BufferAsyncError>> + Send>>;
+
+///
+/// A buffer the timestamps of one frame are copied to and read back from once it is mapped.
+///
+struct Readback{
+    buffer: Buffer<u64>,
+    scopes: Vec<String>,
+    mapping: Option<MapFuture>,
+}
+
+///
+/// A registry of named gpu timings.
+///
+/// Every timed scope writes two timestamps into a query set. After the commands have been
+/// submitted the timestamps are copied to one of several read back buffers, which are mapped
+/// without blocking and read in a later frame once the gpu has finished. The duration of every
+/// name is added to an exponential moving average, so the timings are smoothed across recent
+/// frames and lag a few frames behind.
+///
+/// Requires the TIMESTAMP_QUERY feature.
+///
+pub struct ScopedTimer{
+    query_set: wgpu::QuerySet,
+    resolve_buffer: Buffer<u64>,
+    readbacks: Vec<Readback>,
+    current: usize,
+    capacity: u32,
+    period: f32,
+    smoothing: f32,
+    scopes: Vec<String>,
+    timings: Vec<(String, f32)>,
+}
+
+impl ScopedTimer{
+    const READBACK_COUNT: usize = 3;
+
+    ///
+    /// Creates a timer that can record up to capacity scopes between two calls to collect.
+    ///
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, capacity: u32) -> Self{
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor{
+            label: Some("scoped_timer"),
+            ty: wgpu::QueryType::Timestamp,
+            count: capacity * 2,
+        });
+
+        let resolve_buffer = BufferBuilder::new()
+            .set_usage(wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC)
+            .set_label(Some("scoped_timer_resolve"))
+            .build_empty(device, (capacity * 2) as usize);
+
+        let readbacks = (0..Self::READBACK_COUNT)
+            .map(|_| Readback{
+                buffer: BufferBuilder::new()
+                    .copy_dst().read()
+                    .set_label(Some("scoped_timer_read"))
+                    .build_empty(device, (capacity * 2) as usize),
+                scopes: Vec::new(),
+                mapping: None,
+            }).collect();
+
+        Self{
+            query_set,
+            resolve_buffer,
+            readbacks,
+            current: 0,
+            capacity,
+            period: queue.get_timestamp_period(),
+            smoothing: 0.9,
+            scopes: Vec::new(),
+            timings: Vec::new(),
+        }
+    }
+
+    ///
+    /// Creates a timer if the device supports timestamp queries.
+    ///
+    pub fn for_device(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self>{
+        if device.features().contains(wgpu::Features::TIMESTAMP_QUERY){
+            Some(Self::new(device, queue, 64))
+        }
+        else{
+            None
+        }
+    }
+
+    ///
+    /// Sets the weight of the previous average when adding a new timing.
+    /// 0 disables smoothing.
+    ///
+    pub fn set_smoothing(&mut self, smoothing: f32){
+        self.smoothing = smoothing;
+    }
+
+    ///
+    /// Registers a scope and returns the indices of its begin and end timestamps.
+    /// Returns None if the capacity of the timer is exhausted.
+    ///
+    pub(crate) fn begin_scope(&mut self, name: &str) -> Option<(u32, u32)>{
+        let index = self.scopes.len() as u32;
+        if index >= self.capacity{
+            return None;
+        }
+        self.scopes.push(name.to_string());
+        Some((index * 2, index * 2 + 1))
+    }
+
+    pub(crate) fn query_set(&self) -> &wgpu::QuerySet{
+        &self.query_set
+    }
+
+    ///
+    /// Returns the read back buffer of this frame if it is not still waiting to be read.
+    ///
+    fn free_readback(&self) -> Option<&Readback>{
+        let readback = &self.readbacks[self.current];
+        match readback.mapping{
+            Some(_) => None,
+            None => Some(readback),
+        }
+    }
+
+    ///
+    /// Resolves the timestamps of all scopes recorded since the last collect.
+    /// Has to be called after the last timed scope has been recorded into the encoder.
+    ///
+    /// If all read back buffers are still in use the timestamps of this frame are dropped.
+    ///
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder){
+        if self.scopes.is_empty(){
+            return;
+        }
+        let readback = match self.free_readback(){
+            Some(readback) => readback,
+            None => return,
+        };
+        let count = self.scopes.len() as u32 * 2;
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &readback.buffer,
+            0,
+            (count as usize * std::mem::size_of::<u64>()) as wgpu::BufferAddress
+        );
+    }
+
+    ///
+    /// Starts reading back the timestamps resolved in this frame and updates the averages with
+    /// the timestamps of earlier frames that have been read back in the meantime.
+    /// Has to be called after the encoder passed to resolve has been submitted.
+    ///
+    /// Does not block, the device is only polled.
+    ///
+    pub fn collect(&mut self, device: &wgpu::Device){
+        let scopes = std::mem::take(&mut self.scopes);
+        if !scopes.is_empty() && self.free_readback().is_some(){
+            let readback = &mut self.readbacks[self.current];
+            let size = (scopes.len() * 2 * std::mem::size_of::<u64>()) as wgpu::BufferAddress;
+            readback.mapping = Some(Box::pin(readback.buffer.buffer.slice(..size).map_async(wgpu::MapMode::Read)));
+            readback.scopes = scopes;
+            self.current = (self.current + 1) % self.readbacks.len();
+        }
+
+        device.poll(wgpu::Maintain::Poll);
+        self.read_mapped();
+    }
+
+    ///
+    /// Blocks until all pending timestamps have been read back and updates the averages.
+    ///
+    pub fn flush(&mut self, device: &wgpu::Device){
+        device.poll(wgpu::Maintain::Wait);
+        self.read_mapped();
+    }
+
+    fn read_mapped(&mut self){
+        for i in 0..self.readbacks.len(){
+            // Read back the oldest frames first.
+            let index = (self.current + i) % self.readbacks.len();
+            let readback = &mut self.readbacks[index];
+            let result = match readback.mapping.as_mut().and_then(utils::poll_once){
+                Some(result) => result,
+                None => continue,
+            };
+            readback.mapping = None;
+            let scopes = std::mem::take(&mut readback.scopes);
+            if result.is_err(){
+                continue;
+            }
+
+            let frame = {
+                let size = (scopes.len() * 2 * std::mem::size_of::<u64>()) as wgpu::BufferAddress;
+                let slice = readback.buffer.buffer.slice(..size);
+                let frame = Self::frame_timings(&scopes, bytemuck::cast_slice(&slice.get_mapped_range()), self.period);
+                readback.buffer.buffer.unmap();
+                frame
+            };
+            self.push_frame(frame);
+        }
+    }
+
+    ///
+    /// Sums the durations of the scopes with the same name in milliseconds.
+    ///
+    fn frame_timings(scopes: &[String], timestamps: &[u64], period: f32) -> Vec<(String, f32)>{
+        let mut frame: Vec<(String, f32)> = Vec::new();
+        for (i, name) in scopes.iter().enumerate(){
+            let ticks = timestamps[i * 2 + 1].saturating_sub(timestamps[i * 2]);
+            let ms = ticks as f32 * period / 1_000_000.;
+
+            match frame.iter_mut().find(|(n, _)| n == name){
+                Some((_, t)) => *t += ms,
+                None => frame.push((name.clone(), ms)),
+            }
+        }
+        frame
+    }
+
+    fn push_frame(&mut self, frame: Vec<(String, f32)>){
+        for (name, ms) in frame{
+            match self.timings.iter_mut().find(|(n, _)| *n == name){
+                Some((_, avg)) => *avg = *avg * self.smoothing + ms * (1. - self.smoothing),
+                None => self.timings.push((name, ms)),
+            }
+        }
+    }
+
+    ///
+    /// Returns the smoothed duration of every name in milliseconds.
+    ///
+    pub fn timings(&self) -> Vec<(String, f32)>{
+        self.timings.clone()
+    }
+}
+
+///
+/// A pair of timestamps measuring the duration of one pass
+/// (see ComputePass::new_timed and RenderPassBuilder::begin_timed).
+///
+/// Requires the device to be created with the TIMESTAMP_QUERY feature.
+///
+pub struct TimestampQuery{
+    query_set: wgpu::QuerySet,
+    resolve_buffer: Buffer<u64>,
+    read_buffer: Buffer<u64>,
+    period: f32,
+}
+
+impl TimestampQuery{
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, label: wgpu::Label) -> Self{
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor{
+            label,
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+
+        let resolve_buffer = BufferBuilder::new()
+            .set_usage(wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC)
+            .set_label(label)
+            .build_empty(device, 2);
+
+        let read_buffer = BufferBuilder::new()
+            .copy_dst().read()
+            .set_label(label)
+            .build_empty(device, 2);
+
+        Self{
+            query_set,
+            resolve_buffer,
+            read_buffer,
+            period: queue.get_timestamp_period(),
+        }
+    }
+
+    pub(crate) fn query_set(&self) -> &wgpu::QuerySet{
+        &self.query_set
+    }
+
+    ///
+    /// Resolves the timestamps. Has to be recorded after the timed pass has ended.
+    ///
+    pub fn resolve(&self, encoder: &mut wgpu::CommandEncoder){
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.read_buffer, 0, self.read_buffer.size() as wgpu::BufferAddress);
+    }
+
+    ///
+    /// Returns the time between the start and end of the timed pass in nanoseconds.
+    /// Has to be called after the encoder passed to resolve has been submitted.
+    ///
+    pub fn elapsed_ns(&self, device: &wgpu::Device) -> u64{
+        let timestamps = self.read_buffer.slice(..).map_blocking(device);
+        let ticks = timestamps[1].saturating_sub(timestamps[0]);
+        (ticks as f64 * self.period as f64) as u64
+    }
+}
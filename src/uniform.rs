@@ -2,6 +2,8 @@ use super::binding::Bound;
 use super::binding::CreateBindGroupLayout;
 use super::buffer::*;
 use std::ops::{Deref, DerefMut};
+use std::marker::PhantomData;
+use crate::utils::Align;
 use super::binding;
 use super::binding::BindGroupContent;
 
@@ -187,3 +189,66 @@ impl<C: bytemuck::Pod> CreateBindGroupLayout for BoundUniform<C>{
         Bound::<Uniform<C>>::create_bind_group_layout(device, label)
     }
 }
+
+///
+/// A uniform buffer holding a single value of C without a copy on the cpu.
+///
+/// Uniform buffers have to follow the std140 like layout rules of WGSL: members of type vec3 and
+/// vec4 as well as structs and arrays are aligned to 16 bytes, so C has to be padded accordingly.
+/// The size of the buffer itself is rounded up to a multiple of 16 bytes automatically.
+///
+pub struct UniformBuffer<C: bytemuck::Pod>{
+    pub buffer: wgpu::Buffer,
+    _pd: PhantomData<C>,
+}
+
+impl<C: bytemuck::Pod> UniformBuffer<C>{
+    ///
+    /// Alignment the size of the buffer is rounded up to.
+    ///
+    pub const ALIGNMENT: u64 = 16;
+
+    pub fn new(device: &wgpu::Device, value: &C, label: wgpu::Label) -> Self{
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor{
+            label,
+            size: Self::size(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: true,
+        });
+
+        buffer.slice(..).get_mapped_range_mut()[..std::mem::size_of::<C>()]
+            .copy_from_slice(bytemuck::bytes_of(value));
+        buffer.unmap();
+
+        Self{
+            buffer,
+            _pd: PhantomData,
+        }
+    }
+
+    ///
+    /// The size of the buffer in bytes.
+    ///
+    #[inline]
+    pub fn size() -> u64{
+        (std::mem::size_of::<C>() as u64).align_ceil(Self::ALIGNMENT)
+    }
+
+    pub fn update(&self, queue: &wgpu::Queue, value: &C){
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(value));
+    }
+}
+
+impl<C: bytemuck::Pod> BindGroupContent for UniformBuffer<C>{
+    fn entries(visibility: Option<wgpu::ShaderStages>) -> Vec<binding::BindGroupLayoutEntry>{
+        vec!{
+            binding::BindGroupLayoutEntry::new(visibility.unwrap_or(wgpu::ShaderStages::all()), binding::wgsl::uniform())
+        }
+    }
+
+    fn resources(&self) -> Vec<wgpu::BindingResource> {
+        vec!{
+            self.buffer.as_entire_binding(),
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use std::ops::{RangeBounds, Range, Bound};
 use num_traits::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
 
 ///
@@ -49,6 +53,15 @@ mod test{
         assert_eq!(c, 8);
         assert_eq!(f, 4);
     }
+
+    #[test]
+    fn test_range_clamp(){
+        assert_eq!((0..10).clamp(0..256), 0..10);
+        assert_eq!((0..=9).clamp(0..256), 0..10);
+        assert_eq!((..).clamp(0..256), 0..256);
+        assert_eq!((10..).clamp(0..256), 10..256);
+        assert_eq!((0..512).clamp(0..256), 0..256);
+    }
 }
 
 ///
@@ -87,3 +100,65 @@ impl<T: PrimInt, B: RangeBounds<T>> RangeClamp<T> for B{
         start_bound..end_bound
     }
 }
+
+///
+/// A future resolving to the result of a closure running on its own thread.
+/// Created by spawn_blocking.
+///
+pub struct BlockingTask<T>{
+    state: Arc<Mutex<(Option<T>, Option<Waker>)>>,
+}
+
+impl<T> Future for BlockingTask<T>{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.0.take(){
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+///
+/// Runs f on a new thread and returns a future resolving to its result.
+/// Used to keep expensive cpu work such as image decoding off the calling thread.
+///
+pub fn spawn_blocking<T, F>(f: F) -> BlockingTask<T>
+    where T: Send + 'static, F: FnOnce() -> T + Send + 'static{
+    let state = Arc::new(Mutex::new((None, None)));
+    let thread_state = state.clone();
+    std::thread::spawn(move ||{
+        let result = f();
+        let mut state = thread_state.lock().unwrap();
+        state.0 = Some(result);
+        if let Some(waker) = state.1.take(){
+            waker.wake();
+        }
+    });
+    BlockingTask{
+        state,
+    }
+}
+
+///
+/// Polls the future once without blocking and returns its output if it is ready.
+/// Used for futures like wgpu buffer mappings that are completed by device.poll.
+///
+pub(crate) fn poll_once<F: Future + Unpin>(future: &mut F) -> Option<F::Output>{
+    fn clone(_: *const ()) -> RawWaker{
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()){}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    let waker = unsafe{Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE))};
+    match Pin::new(future).poll(&mut Context::from_waker(&waker)){
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
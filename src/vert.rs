@@ -95,6 +95,56 @@ mod tests {
         float64x4: [f64; 4],
    }
 
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::Zeroable, bytemuck::Pod)]
+    #[derive(VertexLayout)]
+    struct VertexLayoutTest{
+        #[location(0)]
+        pos: [f32; 3],
+        #[location(1)]
+        uv: [f32; 2],
+        #[location(2)]
+        #[norm]
+        color: [u8; 4],
+        #[location(3)]
+        depth: f64,
+    }
+
+    #[test]
+    fn test_vertex_layout(){
+        let layout = VertexLayoutTest::buffer_layout();
+
+        assert_eq!(layout.array_stride, 32);
+        assert_eq!(layout.step_mode, wgpu::VertexStepMode::Vertex);
+        assert_eq!(layout.attributes, &[
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Float32x3, offset: 0, shader_location: 0},
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Float32x2, offset: 12, shader_location: 1},
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Unorm8x4, offset: 20, shader_location: 2},
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Float64, offset: 24, shader_location: 3},
+        ]);
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, bytemuck::Zeroable, bytemuck::Pod)]
+    #[derive(Vert)]
+    struct VertTestF64{
+        #[location = 0]
+        float64: f64,
+        #[location = 1]
+        uint32x2: [u32; 2],
+    }
+
+    #[test]
+    fn test_vert_f64(){
+        let layout = VertTestF64::buffer_layout();
+
+        assert_eq!(layout.array_stride, 16);
+        assert_eq!(layout.attributes, &[
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Float64, offset: 0, shader_location: 0},
+            wgpu::VertexAttribute{format: wgpu::VertexFormat::Uint32x2, offset: 8, shader_location: 1},
+        ]);
+    }
+
     // TODO: write the tests.
     #[test]
     fn test_vert8_buffer_layout(){
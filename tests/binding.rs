@@ -0,0 +1,45 @@
+use ewgpu::*;
+
+#[allow(unused)]
+#[derive(BindGroupContent)]
+struct TestBindGroupContent{
+    indices: Buffer<u32>,
+    #[visibility(VERTEX | FRAGMENT)]
+    vertices: Buffer<f32>,
+    #[visibility = "wgpu::ShaderStages::COMPUTE"]
+    other: Buffer<u32>,
+}
+
+#[test]
+fn derive_bind_group_content_visibility(){
+    let entries = TestBindGroupContent::entries(Some(wgpu::ShaderStages::FRAGMENT));
+
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].visibility, wgpu::ShaderStages::FRAGMENT);
+    assert_eq!(entries[1].visibility, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT);
+    assert_eq!(entries[2].visibility, wgpu::ShaderStages::COMPUTE);
+
+    let entries = TestBindGroupContent::entries(None);
+    assert_eq!(entries[0].visibility, wgpu::ShaderStages::all());
+}
+
+#[test]
+fn bound_replace(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let mut bound = TextureBuilder::new()
+        .clear([16, 16])
+        .build_empty(&gpu.device)
+        .into_bound(&gpu.device);
+
+    let resized = TextureBuilder::new()
+        .clear([32, 32])
+        .build_empty(&gpu.device);
+
+    let old = bound.replace(resized, &gpu.device);
+
+    assert_eq!(old.size.width, 16);
+    assert_eq!(bound.size.width, 32);
+}
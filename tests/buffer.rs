@@ -0,0 +1,114 @@
+use ewgpu::*;
+
+#[test]
+fn read_vec_staged(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let data: Vec<u32> = (0..100).collect();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .storage().copy_src()
+        .build(&gpu.device, &data);
+
+    let read = pollster::block_on(buffer.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(read, data);
+
+    let no_src = BufferBuilder::<u32>::new()
+        .storage()
+        .build(&gpu.device, &data);
+    assert!(pollster::block_on(no_src.read_vec(&gpu.device, &gpu.queue)).is_err());
+}
+
+#[test]
+fn write_bounds(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .storage().copy_dst().copy_src()
+        .build(&gpu.device, &[0; 16]);
+
+    assert!(buffer.write(&gpu.queue, 12, &[1, 2, 3, 4]).is_ok());
+    assert!(buffer.write(&gpu.queue, 13, &[1, 2, 3, 4]).is_err());
+
+    let read = pollster::block_on(buffer.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&read[12..], &[1, 2, 3, 4]);
+}
+
+#[test]
+fn reserve(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let data: Vec<u32> = (0..16).collect();
+
+    let mut buffer = BufferBuilder::<u32>::new()
+        .storage().copy_dst().copy_src()
+        .build(&gpu.device, &data);
+
+    buffer.reserve(&gpu.device, &gpu.queue, 64).unwrap();
+    assert_eq!(buffer.len(), 64);
+
+    let read = pollster::block_on(buffer.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&read[..16], &data[..]);
+}
+
+#[test]
+fn staging_belt(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let buffer = BufferBuilder::<u32>::new()
+        .storage().copy_dst().copy_src()
+        .build(&gpu.device, &[0; 16]);
+
+    let mut belt = StagingBelt::new(1024);
+
+    for frame in 0..2{
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+        belt.write_buffer(&gpu.device, &mut encoder, &buffer, 4, &[frame; 4]).unwrap();
+        assert!(belt.write_buffer(&gpu.device, &mut encoder, &buffer, 14, &[frame; 4]).is_err());
+        belt.finish();
+        gpu.queue.submit(Some(encoder.finish()));
+
+        let recall = belt.recall();
+        gpu.device.poll(wgpu::Maintain::Wait);
+        pollster::block_on(recall);
+
+        let read = pollster::block_on(buffer.read_vec(&gpu.device, &gpu.queue)).unwrap();
+        assert_eq!(&read[4..8], &[frame; 4]);
+    }
+}
+
+#[test]
+fn try_build_validates_usages(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    assert!(BufferBuilder::<u32>::new()
+        .storage().copy_dst()
+        .try_build(&gpu.device, &[1, 2, 3, 4]).is_ok());
+
+    assert!(BufferBuilder::<u32>::new()
+        .try_build_empty(&gpu.device, 4).is_err());
+
+    assert!(BufferBuilder::<u32>::new()
+        .storage()
+        .try_build_empty(&gpu.device, 0).is_err());
+
+    assert!(BufferBuilder::<u32>::new()
+        .storage()
+        .try_build(&gpu.device, &[]).is_err());
+
+    if !gpu.device.features().contains(wgpu::Features::MAPPABLE_PRIMARY_BUFFERS){
+        assert!(BufferBuilder::<u32>::new()
+            .storage().read()
+            .try_build_empty(&gpu.device, 4).is_err());
+    }
+}
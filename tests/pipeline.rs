@@ -0,0 +1,959 @@
+use ewgpu::*;
+
+#[repr(C)]
+#[make_vert]
+struct Vert2{
+    #[location = 0]
+    pub pos: [f32; 2],
+}
+
+const VERT_SRC: &str = "
+#version 460
+#if VERTEX_SHADER
+
+layout(location = 0) in vec2 i_pos;
+
+void main(){
+    gl_Position = vec4(i_pos, 0.0, 1.0);
+}
+
+#endif
+";
+
+const FRAG_SRC: &str = "
+#version 460
+#if FRAGMENT_SHADER
+
+layout(location = 0) out vec4 o_color;
+
+void main(){
+    o_color = vec4(1.0, 0.0, 0.0, 1.0);
+}
+
+#endif
+";
+
+#[test]
+fn depth_pipeline(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_vert_layout(Vert2::buffer_layout())
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_depth_format(wgpu::TextureFormat::Depth32Float)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let vertices = BufferBuilder::new()
+        .vertex()
+        .build(&gpu.device, &[
+            Vert2{pos: [-1.0, -1.0]},
+            Vert2{pos: [1.0, -1.0]},
+            Vert2{pos: [0.0, 1.0]},
+        ]);
+
+    let color = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device, &gpu.queue);
+    let depth = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Depth32Float)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        .build_empty(&gpu.device);
+
+    let color_view = color.texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let depth_view = depth.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(color_view.color_attachment_clear())
+            .set_depth_stencil_attachment(wgpu::RenderPassDepthStencilAttachment{
+                view: &depth_view,
+                depth_ops: Some(wgpu::Operations{
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: true,
+                }),
+                stencil_ops: None,
+            })
+            .begin(encoder, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.set_vertex_buffer(0, vertices.slice(..));
+        rpass_ppl.draw(0..3, 0..1);
+    });
+}
+
+const FULLSCREEN_VERT_SRC: &str = "
+#version 460
+#if VERTEX_SHADER
+
+void main(){
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+#endif
+";
+
+#[test]
+fn draw_procedural(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let color = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device, &gpu.queue);
+    let color_view = color.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(color_view.color_attachment_clear())
+            .begin(encoder, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.draw_procedural(3);
+    });
+
+    let data = pollster::block_on(color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&data[0..4], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn msaa_resolve(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .sample_count(4)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let msaa = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .sample_count(4)
+        .build_empty(&gpu.device);
+    let resolved = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build_empty(&gpu.device);
+
+    let msaa_view = msaa.texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let resolved_view = resolved.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(msaa_view.color_attachment_clear_resolve(&resolved_view))
+            .begin(encoder, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.draw_procedural(3);
+    });
+
+    let data = pollster::block_on(resolved.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&data[0..4], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn multiple_color_attachments_clear(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let targets: Vec<Texture> = (0..3).map(|_|{
+        TextureBuilder::new()
+            .clear([16, 16])
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .build_empty(&gpu.device)
+    }).collect();
+    let views: Vec<wgpu::TextureView> = targets.iter()
+        .map(|target| target.texture.create_view(&wgpu::TextureViewDescriptor::default()))
+        .collect();
+
+    gpu.encode(|_gpu, encoder|{
+        RenderPassBuilder::new()
+            .push_color_attachment_clear(&views[0], wgpu::Color::RED)
+            .push_color_attachment_clear(&views[1], wgpu::Color::GREEN)
+            .push_color_attachment_clear(&views[2], wgpu::Color::BLUE)
+            .begin(encoder, None)
+            .end();
+    });
+
+    let expected = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    for (target, expected) in targets.iter().zip(expected.iter()){
+        let data = pollster::block_on(target.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+        assert_eq!(&data[0..4], expected);
+    }
+}
+
+#[test]
+fn headless_context(){
+    let gpu = GPUContext::headless();
+
+    assert!(gpu.device.features().contains(wgpu::Features::PUSH_CONSTANTS));
+    assert_eq!(gpu.device.limits().max_push_constant_size, 128);
+}
+
+#[test]
+fn context_builder_features(){
+    let gpu = GPUContextBuilder::new()
+        .set_backends(wgpu::Backends::all())
+        .set_features(wgpu::Features::PUSH_CONSTANTS)
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 64,
+            ..Default::default()
+        })
+        .build();
+
+    assert!(gpu.device.features().contains(wgpu::Features::PUSH_CONSTANTS));
+    assert_eq!(gpu.device.limits().max_push_constant_size, 64);
+}
+
+#[test]
+fn max_sample_count(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    assert_eq!(gpu.max_sample_count(wgpu::TextureFormat::Rgba8Unorm), 4);
+    assert_eq!(gpu.max_sample_count(wgpu::TextureFormat::Rgba32Float), 1);
+    assert_eq!(gpu.max_sample_count(wgpu::TextureFormat::R32Uint), 1);
+}
+
+#[test]
+fn strip_index_format(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    assert!(RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_topology(wgpu::PrimitiveTopology::TriangleStrip)
+        .set_strip_index_format(Some(wgpu::IndexFormat::Uint32))
+        .set_layout(&layout)
+        .try_build(&gpu.device).is_ok());
+
+    assert!(RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_strip_index_format(Some(wgpu::IndexFormat::Uint32))
+        .set_layout(&layout)
+        .try_build(&gpu.device).is_err());
+}
+
+#[test]
+fn index_buffer_draw_all(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_vert_layout(Vert2::buffer_layout())
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    let vertices = BufferBuilder::new()
+        .vertex()
+        .build(&gpu.device, &[
+            Vert2{pos: [-1.0, -1.0]},
+            Vert2{pos: [1.0, -1.0]},
+            Vert2{pos: [1.0, 1.0]},
+            Vert2{pos: [-1.0, 1.0]},
+        ]);
+    let indices = IndexBuffer::<u16>::new(&gpu.device, None, &[0, 1, 2, 2, 3, 0]);
+
+    assert_eq!(indices.format(), wgpu::IndexFormat::Uint16);
+
+    let color = TextureBuilder::new()
+        .clear([64, 64])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build_empty(&gpu.device);
+    let color_view = color.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(color_view.color_attachment_clear())
+            .begin(encoder, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.set_vertex_buffer(0, vertices.slice(..));
+        indices.draw_indexed_all(&mut rpass_ppl, 0, 0..1);
+    });
+
+    let data = pollster::block_on(color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert!(data.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
+
+#[repr(C)]
+#[make_inst]
+struct Offset{
+    #[location = 1]
+    pub offset: [f32; 2],
+}
+
+#[test]
+fn instance_buffer(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let layout = pipeline_layout!(&gpu.device,
+        bind_groups: {},
+        push_constants: {}
+    );
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_vert_layout(Vert2::buffer_layout())
+        .push_instance_layout(Offset::buffer_layout())
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    assert_eq!(pipeline.step_modes, vec![wgpu::VertexStepMode::Vertex, wgpu::VertexStepMode::Instance]);
+
+    let instances = InstanceBuffer::new(&gpu.device, None, &[Offset{offset: [0.0, 0.0]}], 1);
+    assert_eq!(instances.slot(), 1);
+}
+
+#[test]
+fn render_target(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let target = RenderTarget::new(&gpu.device, [32, 32], wgpu::TextureFormat::Rgba8Unorm, Some(wgpu::TextureFormat::Depth32Float));
+
+    gpu.encode(|_gpu, encoder|{
+        target.begin_render_pass(encoder, wgpu::Color::GREEN, None).end();
+    });
+
+    let data = pollster::block_on(target.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&data[0..4], &[0, 255, 0, 255]);
+}
+
+#[test]
+fn workgroup_count_rounds_up(){
+    assert_eq!(workgroup_count([100, 1, 1], [64, 1, 1]), [2, 1, 1]);
+    assert_eq!(workgroup_count([128, 16, 0], [64, 8, 1]), [2, 2, 0]);
+    assert_eq!(workgroup_count([u32::MAX, 1, 1], [64, 1, 1]), [u32::MAX / 64 + 1, 1, 1]);
+}
+
+#[test]
+fn reflect_pipeline_layout(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = "
+struct Camera{
+    view_proj: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var<uniform> camera: Camera;
+[[group(1), binding(0)]]
+var t_albedo: texture_2d<f32>;
+[[group(1), binding(1)]]
+var s_albedo: sampler;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] i: u32) -> [[builtin(position)]] vec4<f32>{
+    return camera.view_proj * vec4<f32>(f32(i), 0.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_main() -> [[location(0)]] vec4<f32>{
+    return camera.view_proj[0] * textureSample(t_albedo, s_albedo, vec2<f32>(0.5, 0.5));
+}
+";
+
+    let vshader = ShaderModule::from_src_wgsl(&gpu.device, src, "vs_main", None, None).unwrap();
+    let fshader = ShaderModule::from_src_wgsl(&gpu.device, src, "fs_main", None, None).unwrap();
+
+    let reflected = ReflectedLayout::from_modules(&[&vshader, &fshader]).unwrap();
+
+    assert_eq!(reflected.bind_groups.len(), 2);
+    assert_eq!(reflected.bind_groups[0][0].visibility, wgpu::ShaderStages::VERTEX_FRAGMENT);
+    assert_eq!(reflected.bind_groups[1][0].visibility, wgpu::ShaderStages::FRAGMENT);
+    assert_eq!(reflected.bind_groups[1][1].ty, binding::wgsl::sampler());
+    assert_eq!(reflected.bind_groups[0][0].ty, binding::wgsl::uniform());
+
+    let layout = PipelineLayout::reflect(&gpu.device, &[&vshader, &fshader], None).unwrap();
+    assert_eq!(layout.bind_group_count, 2);
+
+    let missing = ShaderModule::from_src_wgsl(&gpu.device, src, "main", None, None).unwrap();
+    assert!(ReflectedLayout::from_modules(&[&missing]).is_err());
+}
+
+#[test]
+fn fragment_state_blend_targets(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let fshader = ShaderModule::from_src_wgsl(&gpu.device, "
+[[stage(fragment)]]
+fn main() -> [[location(0)]] vec4<f32>{
+    return vec4<f32>(1.0);
+}
+", "main", None, None).unwrap();
+
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let fragment = FragmentState::new(&fshader)
+        .push_target_alpha_blend(format)
+        .push_target_additive(format)
+        .push_target_premultiplied(format);
+
+    assert_eq!(fragment.targets[0].blend, Some(wgpu::BlendState::ALPHA_BLENDING));
+    assert_eq!(fragment.targets[1].blend, Some(ADDITIVE_BLEND_STATE));
+    assert_eq!(fragment.targets[2].blend, Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING));
+}
+
+#[test]
+fn submit_wait_for(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = BufferBuilder::new()
+        .copy_src()
+        .build(&gpu.device, &[1u32, 2, 3, 4]);
+    let dst = BufferBuilder::<u32>::new()
+        .copy_dst().read()
+        .build_empty(&gpu.device, 4);
+
+    let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+    encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 16);
+    let index = gpu.submit(encoder);
+    gpu.wait_for(index);
+
+    assert_eq!(dst.slice(..).map_blocking(&gpu.device).as_ref(), [1, 2, 3, 4]);
+}
+
+#[test]
+fn compute_pipeline_auto_layout(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+#version 460
+#if COMPUTE_SHADER
+layout(set = 0, binding = 0) buffer OutBuffer{
+    uint out_buf[];
+};
+
+void main(){
+    out_buf[gl_GlobalInvocationID.x] = gl_GlobalInvocationID.x;
+}
+#endif
+", None).unwrap();
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .build(&gpu.device);
+
+    assert_eq!(cpipeline.bind_group_count, None);
+    assert_eq!(cpipeline.push_const_count(), 0);
+
+    let out_buf = BufferBuilder::<u32>::new()
+        .storage().copy_src()
+        .build_empty(&gpu.device, 4);
+
+    let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor{
+        label: None,
+        layout: &cpipeline.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry{
+            binding: 0,
+            resource: out_buf.as_entire_binding(),
+        }],
+    });
+
+    gpu.encode(|_gpu, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+        cpass_ppl.set_bind_group(0, &bind_group, &[]);
+        cpass_ppl.dispatch(4, 1, 1);
+    });
+
+    let read = pollster::block_on(out_buf.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(read, [0, 1, 2, 3]);
+}
+
+#[test]
+fn blend_constant(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let constant = wgpu::BlendComponent{
+        src_factor: wgpu::BlendFactor::Constant,
+        dst_factor: wgpu::BlendFactor::Zero,
+        operation: wgpu::BlendOperation::Add,
+    };
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target(wgpu::ColorTargetState{
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            blend: Some(wgpu::BlendState{
+                color: constant,
+                alpha: constant,
+            }),
+            write_mask: wgpu::ColorWrites::all(),
+        })
+        .build(&gpu.device);
+
+    let target = RenderTarget::new(&gpu.device, [16, 16], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = target.begin_render_pass(encoder, wgpu::Color::BLACK, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.set_blend_constant(wgpu::Color{r: 0., g: 1., b: 0., a: 1.});
+        rpass_ppl.set_stencil_reference(0);
+        rpass_ppl.draw_procedural(3);
+    });
+
+    let data = pollster::block_on(target.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(&data[0..4], &[0, 255, 0, 255]);
+}
+
+#[test]
+fn mesh_drawable(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_vert_layout(Vert2::buffer_layout())
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device);
+
+    let mesh = Mesh::<Vert2, u32>::new(&gpu.device, &[
+        Vert2{pos: [-1.0, -1.0]},
+        Vert2{pos: [1.0, -1.0]},
+        Vert2{pos: [1.0, 1.0]},
+        Vert2{pos: [-1.0, 1.0]},
+    ], &[0, 1, 2, 2, 3, 0], None);
+
+    let target = RenderTarget::new(&gpu.device, [64, 64], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = target.begin_render_pass(encoder, wgpu::Color::BLACK, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        mesh.draw(&mut rpass_ppl);
+    });
+
+    let data = pollster::block_on(target.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert!(data.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
+
+#[test]
+fn multiview(){
+    let adapter_features = GPUContextBuilder::new()
+        .set_features_util()
+        .build()
+        .adapter.features();
+    if !adapter_features.contains(wgpu::Features::MULTIVIEW){
+        return;
+    }
+
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .enable_feature(wgpu::Features::MULTIVIEW)
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .set_multiview(std::num::NonZeroU32::new(2))
+        .build(&gpu.device);
+
+    assert_eq!(pipeline.multiview, std::num::NonZeroU32::new(2));
+
+    let layers = TextureBuilder::new()
+        .clear([16, 16, 2])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build_empty(&gpu.device);
+    let view = layers.multiview_view();
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = RenderPassBuilder::new()
+            .push_color_attachment(view.color_attachment_clear())
+            .begin(encoder, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.draw_procedural(3);
+    });
+}
+
+#[test]
+fn wgsl_entry_points(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = "
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] i: u32) -> [[builtin(position)]] vec4<f32>{
+    let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_main() -> [[location(0)]] vec4<f32>{
+    return vec4<f32>(0.0, 0.0, 1.0, 1.0);
+}
+";
+
+    let vshader = VertexShader::from_wgsl(&gpu.device, src, "vs_main", None).unwrap();
+    let fshader = FragmentShader::from_wgsl(&gpu.device, src, "fs_main", None).unwrap();
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device);
+
+    let target = RenderTarget::new(&gpu.device, [16, 16], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = target.begin_render_pass(encoder, wgpu::Color::BLACK, None);
+
+        let mut rpass_ppl = rpass.set_pipeline(&pipeline);
+        rpass_ppl.draw_procedural(3);
+    });
+
+    let data = pollster::block_on(target.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert!(data.chunks(4).all(|pixel| pixel == [0, 0, 255, 255]));
+}
+
+#[test]
+fn push_interleaved(){
+    let vertex = VertexBufferLayout::interleaved(wgpu::VertexStepMode::Vertex, &[
+        wgpu::VertexFormat::Float32x3,
+        wgpu::VertexFormat::Uint8x2,
+        wgpu::VertexFormat::Float32,
+    ], 0);
+
+    assert_eq!(vertex.attributes.iter().map(|a| a.offset).collect::<Vec<_>>(), [0, 12, 16]);
+    assert_eq!(vertex.array_stride, 20);
+    assert_eq!(vertex.next_location(), 3);
+
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+    let shader = ShaderModule::from_src_wgsl(&gpu.device, "
+[[stage(vertex)]]
+fn main() -> [[builtin(position)]] vec4<f32>{
+    return vec4<f32>(0.0);
+}
+", "main", None, None).unwrap();
+
+    let state = VertexState::new(&shader)
+        .push_interleaved(wgpu::VertexStepMode::Vertex, &[
+            wgpu::VertexFormat::Float32x3,
+            wgpu::VertexFormat::Uint8x2,
+            wgpu::VertexFormat::Float32,
+        ])
+        .push_interleaved(wgpu::VertexStepMode::Instance, &[wgpu::VertexFormat::Float32x4]);
+
+    assert_eq!(state.vertex_buffer_layouts[0], vertex);
+    assert_eq!(state.vertex_buffer_layouts[1].attributes[0].shader_location, 3);
+    assert_eq!(state.vertex_buffer_layouts[1].array_stride, 16);
+}
+
+#[test]
+fn render_bundle(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let vshader = VertexShader::from_src(&gpu.device, FULLSCREEN_VERT_SRC, None).unwrap();
+    let fshader = FragmentShader::from_src(&gpu.device, FRAG_SRC, None).unwrap();
+
+    let pipeline = RenderPipelineBuilder::new(&vshader, &fshader)
+        .push_target_replace(wgpu::TextureFormat::Rgba8Unorm)
+        .build(&gpu.device);
+
+    let mut bundle = RenderBundleBuilder::new(&gpu.device, &wgpu::RenderBundleEncoderDescriptor{
+        label: None,
+        color_formats: &[wgpu::TextureFormat::Rgba8Unorm],
+        depth_stencil: None,
+        sample_count: 1,
+        multiview: None,
+    });
+    bundle.set_pipeline(&pipeline);
+    bundle.draw(0..3, 0..1);
+    let bundle = bundle.finish(None);
+
+    let target = RenderTarget::new(&gpu.device, [64, 64], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    gpu.encode(|_gpu, encoder|{
+        let mut rpass = target.begin_render_pass(encoder, wgpu::Color::BLACK, None);
+        rpass.execute_bundles(&[&bundle]);
+    });
+
+    let data = pollster::block_on(target.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert!(data.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
+
+#[test]
+fn timed_dispatch(){
+    let adapter_features = GPUContextBuilder::new()
+        .set_features_util()
+        .build()
+        .adapter.features();
+    let timestamps = adapter_features.contains(wgpu::Features::TIMESTAMP_QUERY);
+
+    let mut builder = GPUContextBuilder::new()
+        .set_features_util();
+    if timestamps{
+        builder = builder.enable_feature(wgpu::Features::TIMESTAMP_QUERY);
+    }
+    let mut gpu = builder.build();
+    assert_eq!(gpu.timer.is_some(), timestamps);
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+#version 460
+#if COMPUTE_SHADER
+layout(set = 0, binding = 0) buffer OutBuffer{
+    uint out_buf[];
+};
+
+void main(){
+    out_buf[gl_GlobalInvocationID.x] = 1;
+}
+#endif
+", None).unwrap();
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .build(&gpu.device);
+
+    let out_buf = BufferBuilder::<u32>::new()
+        .storage().copy_src()
+        .build_empty(&gpu.device, 4);
+
+    let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor{
+        label: None,
+        layout: &cpipeline.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry{
+            binding: 0,
+            resource: out_buf.as_entire_binding(),
+        }],
+    });
+
+    // More frames than the capacity of the timer, the scopes have to be collected every frame.
+    for _ in 0..100{
+        gpu.encode(|gpu, encoder|{
+            let mut cpass = ComputePass::new(encoder, None);
+            let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+            cpass_ppl.set_bind_group(0, &bind_group, &[]);
+            cpass_ppl.timed_dispatch(gpu.timer.as_mut(), "fill", 4, 1, 1);
+        });
+    }
+
+    let read = pollster::block_on(out_buf.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(read, [1, 1, 1, 1]);
+
+    // The timings are read back without blocking, wait for the last frames.
+    if let Some(timer) = &mut gpu.timer{
+        timer.flush(&gpu.device);
+    }
+
+    let timings = gpu.timings();
+    if timestamps{
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].0, "fill");
+        assert!(timings[0].1 >= 0.);
+    }
+    else{
+        assert!(timings.is_empty());
+    }
+}
+
+#[test]
+fn timestamp_query(){
+    let adapter_features = GPUContextBuilder::new()
+        .set_features_util()
+        .build()
+        .adapter.features();
+    if !adapter_features.contains(wgpu::Features::TIMESTAMP_QUERY){
+        return;
+    }
+
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .enable_feature(wgpu::Features::TIMESTAMP_QUERY)
+        .build();
+
+    let timestamps = TimestampQuery::new(&gpu.device, &gpu.queue, None);
+    let target = RenderTarget::new(&gpu.device, [64, 64], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    gpu.encode(|_gpu, encoder|{
+        RenderPassBuilder::new()
+            .push_color_attachment(target.color_view.color_attachment_clear())
+            .begin_timed(encoder, &timestamps, None)
+            .end();
+        timestamps.resolve(encoder);
+    });
+
+    // Only checks that the timestamps can be read back, the duration of an empty pass may be 0.
+    timestamps.elapsed_ns(&gpu.device);
+}
+
+#[test]
+fn compute_uniforms_uniform_backing(){
+    // Without PUSH_CONSTANTS the parameters are passed through the uniform buffer.
+    let mut gpu = GPUContextBuilder::new()
+        .set_features(wgpu::Features::empty())
+        .build();
+
+    let params = ComputeUniforms::<u32>::new(&gpu.device, 0, 1);
+    assert!(!params.uses_push_constants());
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+#version 460
+#if COMPUTE_SHADER
+layout(set = 0, binding = 0) buffer OutBuffer{
+    uint out_buf[];
+};
+layout(set = 1, binding = 0) uniform Params{
+    uint index;
+};
+
+void main(){
+    out_buf[index] = index + 1;
+}
+#endif
+", None).unwrap();
+
+    let out_buf = BufferBuilder::<u32>::new()
+        .storage().copy_src()
+        .build_empty(&gpu.device, 4);
+
+    let (out_layout, out_bind_group) = binding::quick_bind_group(&gpu.device, &[
+        (wgpu::ShaderStages::COMPUTE, binding::wgsl::buffer(false), out_buf.as_entire_binding()),
+    ]);
+
+    let layout = params.push_layout(
+        PipelineLayoutBuilder::new()
+        .push_bind_group(&out_layout)
+    ).build(&gpu.device, None);
+
+    let cpipeline = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .build(&gpu.device);
+
+    gpu.encode(|gpu, encoder|{
+        let mut cpass = ComputePass::new(encoder, None);
+        let mut cpass_ppl = cpass.set_pipeline(&cpipeline);
+        cpass_ppl.set_bind_group(0, &out_bind_group, &[]);
+        for i in 0..4u32{
+            cpass_ppl.set_params(&gpu.queue, &params, &i);
+            cpass_ppl.dispatch(1, 1, 1);
+        }
+    });
+
+    let read = pollster::block_on(out_buf.read_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(read, [1, 2, 3, 4]);
+}
+
+#[test]
+fn texture_depth_attachment(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let color = RenderTarget::new(&gpu.device, [16, 16], wgpu::TextureFormat::Rgba8Unorm, None);
+    let depth = TextureBuilder::new()
+        .clear([16, 16])
+        .depth(wgpu::TextureFormat::Depth24PlusStencil8)
+        .build_empty(&gpu.device);
+
+    let stencil_ops = wgpu::Operations{
+        load: wgpu::LoadOp::Load,
+        store: true,
+    };
+    let attachment = depth.depth_attachment_load_with(Some(stencil_ops));
+    assert_eq!(attachment.stencil_ops, Some(stencil_ops));
+    assert_eq!(depth.depth_attachment_load().stencil_ops, None);
+
+    gpu.encode(|_gpu, encoder|{
+        RenderPassBuilder::new()
+            .push_color_attachment(color.color_view.color_attachment_clear())
+            .set_depth_stencil_attachment(depth.depth_attachment_clear_with(1.0, Some(wgpu::Operations{
+                load: wgpu::LoadOp::Clear(0),
+                store: true,
+            })))
+            .begin(encoder, None)
+            .end();
+
+        RenderPassBuilder::new()
+            .push_color_attachment(color.color_view.color_attachment_load())
+            .set_depth_stencil_attachment(depth.depth_attachment_load_with(Some(stencil_ops)))
+            .begin(encoder, None)
+            .end();
+    });
+}
@@ -66,3 +66,65 @@ fn push_const_compute(){
     });
     assert_eq!(out_buf.slice(..).map_blocking(&gpu.device).as_ref(), [3]);
 }
+
+#[test]
+fn push_const_ranges_aligned(){
+    let ranges = push_const_ranges(&[
+        <[u8; 5]>::push_const_layout(wgpu::ShaderStages::VERTEX),
+        <[u32; 3]>::push_const_layout(wgpu::ShaderStages::FRAGMENT),
+    ]);
+
+    assert_eq!(ranges[0].range, 0..8);
+    assert_eq!(ranges[1].range, 8..20);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn push_const_range_out_of_bounds(){
+    let ranges = push_const_ranges(&[
+        u32::push_const_layout(wgpu::ShaderStages::VERTEX),
+    ]);
+
+    push_const_range::<u32>(&ranges, 1);
+}
+
+#[test]
+#[should_panic(expected = "does not fit")]
+fn push_const_range_too_large(){
+    let ranges = push_const_ranges(&[
+        u32::push_const_layout(wgpu::ShaderStages::VERTEX),
+        u32::push_const_layout(wgpu::ShaderStages::FRAGMENT),
+    ]);
+
+    assert_eq!(push_const_range::<u32>(&ranges, 1).range, 4..8);
+    push_const_range::<[u32; 2]>(&ranges, 0);
+}
+
+#[test]
+fn compute_try_build_push_const_stages(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .set_limits(wgpu::Limits{
+            max_push_constant_size: 128,
+            ..Default::default()
+        })
+        .build();
+
+    let cshader = ComputeShader::from_src(&gpu.device, "
+            #version 460
+            #if COMPUTE_SHADER
+            void main(){}
+            #endif
+            ", None).unwrap();
+
+    let layout = PipelineLayoutBuilder::new()
+        .push_const_layout(u32::push_const_layout(wgpu::ShaderStages::VERTEX))
+        .build(&gpu.device, None);
+
+    let err = ComputePipelineBuilder::new(&cshader)
+        .set_layout(&layout)
+        .try_build(&gpu.device)
+        .err().unwrap();
+
+    assert!(err.to_string().contains("not visible to the compute stage"), "{}", err);
+}
@@ -0,0 +1,97 @@
+use ewgpu::*;
+
+#[test]
+fn load_wgsl_include_cycle(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let dir = std::env::temp_dir().join("ewgpu_load_wgsl_include_cycle");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.wgsl"), "#include \"b.wgsl\"\n").unwrap();
+    std::fs::write(dir.join("b.wgsl"), "#include \"a.wgsl\"\n").unwrap();
+
+    let module = ShaderModule::load_wgsl(&gpu.device, &dir.join("a.wgsl"), "main", None);
+
+    assert!(module.is_err());
+}
+
+#[test]
+fn glsl_error_log(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let err = FragmentShader::from_src(&gpu.device, "
+#version 460
+#if FRAGMENT_SHADER
+void main(){
+    undefined_function();
+}
+#endif
+", Some("broken")).unwrap_err();
+
+    let msg = err.to_string();
+    assert!(msg.contains("broken:5"), "{}", msg);
+}
+
+#[test]
+fn load_auto(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    assert_eq!(ShaderModule::kind_from_path(std::path::Path::new("a.vert")), Some(shaderc::ShaderKind::Vertex));
+    assert_eq!(ShaderModule::kind_from_path(std::path::Path::new("a.glsl")), None);
+
+    let dir = std::env::temp_dir().join("ewgpu_load_auto");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.frag"), "
+#version 460
+layout(location = 0) out vec4 o_color;
+void main(){
+    o_color = vec4(1.0);
+}
+").unwrap();
+    std::fs::write(dir.join("a.glsl"), "").unwrap();
+
+    let module = ShaderModule::load_auto(&gpu.device, &dir.join("a.frag"), "main", None).unwrap();
+    assert_eq!(module.src_files.len(), 1);
+
+    let err = ShaderModule::load_auto(&gpu.device, &dir.join("a.glsl"), "main", None).unwrap_err();
+    assert!(err.to_string().contains("vert"), "{}", err);
+}
+
+#[test]
+fn load_glsl_missing_file(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let path = std::env::temp_dir().join("ewgpu_missing_shader.frag");
+    let err = FragmentShader::load(&gpu.device, &path, None).unwrap_err();
+
+    assert!(err.to_string().contains("ewgpu_missing_shader.frag"), "{}", err);
+}
+
+#[test]
+fn load_glsl_include(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let dir = std::env::temp_dir().join("ewgpu_load_glsl_include");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("color.glsl"), "const vec4 COLOR = vec4(1.0);\n").unwrap();
+    std::fs::write(dir.join("main.frag"), "
+#version 460
+#include \"color.glsl\"
+layout(location = 0) out vec4 o_color;
+void main(){
+    o_color = COLOR;
+}
+").unwrap();
+
+    let shader = FragmentShader::load(&gpu.device, &dir.join("main.frag"), None).unwrap();
+    assert_eq!(shader.src_files.len(), 2);
+}
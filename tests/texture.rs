@@ -0,0 +1,627 @@
+use ewgpu::*;
+
+#[test]
+fn copy_to_buffer_padded(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let width = 100;
+    let height = 100;
+    let data: Vec<u8> = (0..(width * height * 4)).map(|i| (i % 251) as u8).collect();
+
+    let texture = TextureBuilder::new()
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .from_raw(data.clone(), wgpu::Extent3d{
+            width,
+            height,
+            depth_or_array_layers: 1,
+        })
+        .build(&gpu.device, &gpu.queue);
+
+    // 400 bytes per row padded to 512.
+    let padded_bytes_per_row = 512;
+    let mut buffer = BufferBuilder::<u8>::new()
+        .copy_dst().read()
+        .build_empty(&gpu.device, (padded_bytes_per_row * height) as usize);
+
+    let mut layout = None;
+    gpu.encode(|_gpu, encoder|{
+        layout = Some(texture.slice(.., .., ..).copy_to_buffer(encoder, &mut buffer, 0));
+    });
+    let layout = layout.unwrap();
+
+    assert_eq!(layout.bytes_per_row.unwrap().get(), padded_bytes_per_row);
+
+    let view = buffer.slice(..).map_blocking(&gpu.device);
+    for row in 0..height as usize{
+        let src = &data[(row * width as usize * 4)..((row + 1) * width as usize * 4)];
+        let dst_start = row * padded_bytes_per_row as usize;
+        assert_eq!(&view[dst_start..(dst_start + width as usize * 4)], src);
+    }
+}
+
+#[test]
+fn slice_bounds(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([256, 256])
+        .build(&gpu.device, &gpu.queue);
+
+    let slice = texture.slice(0..10, 0..10, ..);
+
+    assert_eq!(slice.origin(), wgpu::Origin3d{x: 0, y: 0, z: 0});
+    assert_eq!(slice.extent(), wgpu::Extent3d{width: 10, height: 10, depth_or_array_layers: 1});
+}
+
+#[test]
+fn generate_mipmaps(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([256, 256])
+        .mip_levels(9)
+        .build(&gpu.device, &gpu.queue);
+
+    assert_eq!(texture.mip_level_count, 9);
+
+    gpu.encode(|gpu, encoder|{
+        texture.generate_mipmaps(&gpu.device, encoder);
+    });
+
+    // A checkerboard of black and white pixels in both layers has to average to gray in the
+    // lower mip levels of every layer.
+    let checker: Vec<u8> = (0..2 * 256 * 256).flat_map(|i|{
+        let (x, y) = (i % 256, (i / 256) % 256);
+        let value = if (x + y) % 2 == 0 {255} else {0};
+        [value, value, value, 255]
+    }).collect();
+
+    let layers = TextureBuilder::new()
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .from_raw(checker, wgpu::Extent3d{
+            width: 256,
+            height: 256,
+            depth_or_array_layers: 2,
+        })
+        .mip_levels(9)
+        .build(&gpu.device, &gpu.queue);
+
+    gpu.encode(|gpu, encoder|{
+        layers.generate_mipmaps(&gpu.device, encoder);
+    });
+
+    for layer in 0..2{
+        // Mip level 2 is 64 pixels wide, which gives rows of the required 256 byte alignment.
+        let buffer = BufferBuilder::<u8>::new()
+            .copy_dst().read()
+            .build_empty(&gpu.device, 64 * 64 * 4);
+
+        let mut encoder = gpu.device.create_command_encoder(&wgpu::CommandEncoderDescriptor{label: None});
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture{
+                texture: &layers.texture,
+                mip_level: 2,
+                origin: wgpu::Origin3d{x: 0, y: 0, z: layer},
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer{
+                buffer: &buffer.buffer,
+                layout: wgpu::ImageDataLayout{
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(64 * 4),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d{
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+        );
+        gpu.queue.submit(Some(encoder.finish()));
+
+        let data = buffer.slice(..).map_blocking(&gpu.device);
+        assert!(data.as_ref().chunks(4).all(|pixel| (pixel[0] as i32 - 128).abs() <= 2 && pixel[3] == 255));
+    }
+}
+
+#[test]
+#[should_panic(expected = "mip levels")]
+fn mip_levels_too_many(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    TextureBuilder::new()
+        .clear([256, 256])
+        .mip_levels(10)
+        .build_empty(&gpu.device);
+}
+
+#[test]
+fn read_to_vec(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let data: Vec<u8> = (0..(100 * 100 * 4)).map(|i| (i % 251) as u8).collect();
+
+    let texture = TextureBuilder::new()
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .from_raw(data.clone(), wgpu::Extent3d{
+            width: 100,
+            height: 100,
+            depth_or_array_layers: 1,
+        })
+        .build(&gpu.device, &gpu.queue);
+
+    let read = pollster::block_on(texture.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+
+    assert_eq!(read, data);
+}
+
+#[test]
+fn format_block_size(){
+    assert_eq!(texture::bytes_per_pixel(wgpu::TextureFormat::Rgba8Unorm), 4);
+    assert_eq!(texture::block_dimensions(wgpu::TextureFormat::Rgba8Unorm), (1, 1));
+    assert_eq!(texture::bytes_per_pixel(wgpu::TextureFormat::Bc1RgbaUnorm), 8);
+    assert_eq!(texture::block_dimensions(wgpu::TextureFormat::Bc1RgbaUnorm), (4, 4));
+}
+
+#[test]
+fn texture_viewer_fit_viewport(){
+    assert_eq!(TextureViewer::fit_viewport([200, 100], [400, 400]), [0.0, 100.0, 400.0, 200.0]);
+    assert_eq!(TextureViewer::fit_viewport([100, 200], [400, 400]), [100.0, 0.0, 200.0, 400.0]);
+}
+
+#[test]
+fn new_storage(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    assert!(Texture::new_storage(&gpu.device, [64, 64], wgpu::TextureFormat::Rgba8Unorm, None).is_ok());
+    assert!(Texture::new_storage(&gpu.device, [64, 64], wgpu::TextureFormat::Depth32Float, None).is_err());
+}
+
+#[test]
+fn brdf_lut(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let lut = Texture::brdf_lut(&gpu.device, &gpu.queue, 64);
+
+    assert_eq!(lut.format, wgpu::TextureFormat::Rg16Float);
+    assert_eq!(lut.size, wgpu::Extent3d{width: 64, height: 64, depth_or_array_layers: 1});
+}
+
+#[test]
+fn prefilter_env(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let env = TextureBuilder::new()
+        .clear([32, 32, 6])
+        .format(wgpu::TextureFormat::Rgba16Float)
+        .build_empty(&gpu.device);
+
+    let prefiltered = Texture::prefilter_env(&gpu.device, &gpu.queue, &env, 4);
+
+    assert_eq!(prefiltered.mip_level_count, 4);
+    assert_eq!(prefiltered.size, env.size);
+
+    let cube_view = prefiltered.cube_view();
+    binding::quick_bind_group(&gpu.device, &[
+        (
+            wgpu::ShaderStages::FRAGMENT,
+            wgpu::BindingType::Texture{
+                sample_type: wgpu::TextureSampleType::Float{filterable: true},
+                view_dimension: wgpu::TextureViewDimension::Cube,
+                multisampled: false,
+            },
+            wgpu::BindingResource::TextureView(&cube_view),
+        ),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "mip levels")]
+fn prefilter_env_too_many_mip_levels(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let env = TextureBuilder::new()
+        .clear([32, 32, 6])
+        .format(wgpu::TextureFormat::Rgba16Float)
+        .build_empty(&gpu.device);
+
+    Texture::prefilter_env(&gpu.device, &gpu.queue, &env, 7);
+}
+
+#[test]
+fn irradiance_map(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let env = TextureBuilder::new()
+        .clear([32, 32, 6])
+        .format(wgpu::TextureFormat::Rgba16Float)
+        .build_empty(&gpu.device);
+
+    let irradiance = Texture::irradiance_map(&gpu.device, &gpu.queue, &env, 8);
+
+    assert_eq!(irradiance.mip_level_count, 1);
+    assert_eq!(irradiance.size, wgpu::Extent3d{
+        width: 8,
+        height: 8,
+        depth_or_array_layers: 6,
+    });
+}
+
+#[test]
+fn from_bytes_async(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let img = image::DynamicImage::ImageRgba8(image::RgbaImage::new(16, 8));
+    let mut bytes = Vec::new();
+    img.write_to(&mut bytes, image::ImageOutputFormat::Png).unwrap();
+
+    let texture = pollster::block_on(Texture::from_bytes_async(
+        &gpu.device, &gpu.queue, bytes, None, wgpu::TextureFormat::Rgba8UnormSrgb
+    )).unwrap();
+
+    assert_eq!(texture.size, wgpu::Extent3d{width: 16, height: 8, depth_or_array_layers: 1});
+
+    assert!(pollster::block_on(Texture::from_bytes_async(
+        &gpu.device, &gpu.queue, vec![0; 16], None, wgpu::TextureFormat::Rgba8UnormSrgb
+    )).is_err());
+}
+
+#[test]
+fn texture_array(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let texture = TextureBuilder::new()
+        .clear([16, 16, 4])
+        .format(wgpu::TextureFormat::Rgba8Unorm)
+        .build_empty(&gpu.device);
+
+    let array = TextureArray::new(texture);
+    assert_eq!(array.layers(), 4);
+
+    let entries = TextureArray::entries(None);
+    assert_eq!(entries[0].ty, binding::wgsl::texture_2d_array());
+
+    let _bound = array.into_bound(&gpu.device);
+}
+
+#[test]
+fn depth_texture_comparison_sampler(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let shadow_map = DepthTexture::new(TextureBuilder::new()
+        .clear([256, 256])
+        .depth(wgpu::TextureFormat::Depth32Float)
+        .comparison_sampler(wgpu::CompareFunction::Greater)
+        .build_empty(&gpu.device));
+
+    let entries = DepthTexture::entries(None);
+    assert_eq!(entries[1].ty, binding::wgsl::sampler_comparison());
+
+    let _bound = shadow_map.into_bound(&gpu.device);
+}
+
+#[test]
+fn sampler_options(){
+    let builder = TextureBuilder::new()
+        .address_mode(wgpu::AddressMode::Repeat)
+        .mag_filter(wgpu::FilterMode::Nearest)
+        .min_filter(wgpu::FilterMode::Nearest)
+        .mipmap_filter(wgpu::FilterMode::Nearest);
+
+    assert_eq!(builder.sampler_descriptor.address_mode_u, wgpu::AddressMode::Repeat);
+    assert_eq!(builder.sampler_descriptor.address_mode_v, wgpu::AddressMode::Repeat);
+    assert_eq!(builder.sampler_descriptor.address_mode_w, wgpu::AddressMode::Repeat);
+    assert_eq!(builder.sampler_descriptor.mag_filter, wgpu::FilterMode::Nearest);
+    assert_eq!(builder.sampler_descriptor.min_filter, wgpu::FilterMode::Nearest);
+    assert_eq!(builder.sampler_descriptor.mipmap_filter, wgpu::FilterMode::Nearest);
+}
+
+#[derive(BindGroupContent)]
+struct SharedSamplerContent{
+    albedo: TextureView,
+    normal: TextureView,
+    sampler: Sampler,
+}
+
+#[test]
+fn shared_sampler(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let albedo = TextureBuilder::new()
+        .clear([16, 16])
+        .build_empty(&gpu.device);
+    let normal = TextureBuilder::new()
+        .clear([16, 16])
+        .build_empty(&gpu.device);
+
+    let content = SharedSamplerContent{
+        albedo: albedo.texture_view(),
+        normal: normal.texture_view(),
+        sampler: Sampler::linear(&gpu.device, wgpu::AddressMode::Repeat),
+    };
+
+    assert_eq!(SharedSamplerContent::entries(None).len(), 3);
+
+    let _bound = content.into_bound(&gpu.device);
+}
+
+#[test]
+fn blit(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let src = RenderTarget::new(&gpu.device, [64, 64], wgpu::TextureFormat::Rgba8Unorm, None);
+    let dst = RenderTarget::new(&gpu.device, [32, 32], wgpu::TextureFormat::Rgba8Unorm, None);
+
+    let blit = Blit::new(&gpu.device, wgpu::TextureFormat::Rgba8Unorm);
+
+    gpu.encode(|gpu, encoder|{
+        src.begin_render_pass(encoder, wgpu::Color::RED, None).end();
+        blit.blit(&gpu.device, encoder, &src.color, &dst.color_view);
+    });
+
+    let data = pollster::block_on(dst.color.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert!(data.chunks(4).all(|pixel| pixel == [255, 0, 0, 255]));
+}
+
+fn ktx2_rgba8(width: u32, height: u32, data: &[u8]) -> Vec<u8>{
+    let mut bytes = vec![0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+    // vkFormat, typeSize, width, height, depth, layers, faces, levels, supercompression
+    for word in [37, 1, width, height, 0, 0, 1, 1, 0]{
+        bytes.extend_from_slice(&u32::to_le_bytes(word));
+    }
+    // dfd, kvd and sgd index.
+    bytes.extend_from_slice(&[0; 32]);
+    let offset = bytes.len() as u64 + 24;
+    for word in [offset, data.len() as u64, data.len() as u64]{
+        bytes.extend_from_slice(&u64::to_le_bytes(word));
+    }
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn load_ktx2(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let data: Vec<u8> = (0..(64 * 4 * 4)).map(|i| (i % 251) as u8).collect();
+    let bytes = ktx2_rgba8(64, 4, &data);
+
+    let texture = Texture::load_ktx2(&gpu.device, &gpu.queue, &bytes, None).unwrap();
+    assert_eq!(texture.format, wgpu::TextureFormat::Rgba8Unorm);
+    assert_eq!(texture.size, wgpu::Extent3d{width: 64, height: 4, depth_or_array_layers: 1});
+
+    let read = pollster::block_on(texture.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+    assert_eq!(read, data);
+
+    assert!(Texture::load_ktx2(&gpu.device, &gpu.queue, &bytes[..bytes.len() - 1], None).is_err());
+    assert!(Texture::load_ktx2(&gpu.device, &gpu.queue, &[0; 80], None).is_err());
+}
+
+#[test]
+fn load_ktx2_corrupted_header(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let data = vec![0u8; 64 * 4 * 4];
+    let bytes = ktx2_rgba8(64, 4, &data);
+    let patched = |offset: usize, word: &[u8]|{
+        let mut bytes = bytes.clone();
+        bytes[offset..offset + word.len()].copy_from_slice(word);
+        bytes
+    };
+
+    // Width of 0.
+    let err = Texture::load_ktx2(&gpu.device, &gpu.queue, &patched(20, &0u32.to_le_bytes()), None).unwrap_err();
+    assert!(err.to_string().contains("width"), "{}", err);
+
+    // More mip levels than a 64x4 texture can have, including counts that would overflow shifts.
+    for level_count in [8u32, 32, 40]{
+        let err = Texture::load_ktx2(&gpu.device, &gpu.queue, &patched(40, &level_count.to_le_bytes()), None).unwrap_err();
+        assert!(err.to_string().contains("mip levels"), "{}", err);
+    }
+
+    // BC1 texture whose width is not a multiple of the 4x4 block size.
+    let mut bc = patched(12, &131u32.to_le_bytes());
+    bc[20..24].copy_from_slice(&30u32.to_le_bytes());
+    let err = Texture::load_ktx2(&gpu.device, &gpu.queue, &bc, None).unwrap_err();
+    assert!(err.to_string().contains("block size"), "{}", err);
+
+    // Offset and length overflowing when added.
+    let err = Texture::load_ktx2(&gpu.device, &gpu.queue, &patched(80, &u64::MAX.to_le_bytes()), None).unwrap_err();
+    assert!(err.to_string().contains("outside"), "{}", err);
+    let err = Texture::load_ktx2(&gpu.device, &gpu.queue, &patched(88, &u64::MAX.to_le_bytes()), None).unwrap_err();
+    assert!(err.to_string().contains("outside"), "{}", err);
+}
+
+#[test]
+fn load_from_path_async(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let path = std::env::temp_dir().join("ewgpu_load_from_path_async.png");
+    image::RgbaImage::new(8, 4).save(&path).unwrap();
+
+    let texture = pollster::block_on(Texture::load_from_path_async(
+        &gpu.device, &gpu.queue, path, None, wgpu::TextureFormat::Rgba8UnormSrgb
+    )).unwrap();
+
+    assert_eq!(texture.size, wgpu::Extent3d{width: 8, height: 4, depth_or_array_layers: 1});
+
+    assert!(pollster::block_on(Texture::load_from_path_async(
+        &gpu.device, &gpu.queue, std::env::temp_dir().join("ewgpu_missing.png"), None, wgpu::TextureFormat::Rgba8UnormSrgb
+    )).is_err());
+}
+
+#[test]
+fn clear(){
+    let adapter_features = GPUContextBuilder::new()
+        .set_features_util()
+        .build()
+        .adapter.features();
+
+    // Clears with the render pass fallback and with clear_texture if the adapter supports it.
+    let mut features = vec![wgpu::Features::empty()];
+    if adapter_features.contains(wgpu::Features::CLEAR_COMMANDS){
+        features.push(wgpu::Features::CLEAR_COMMANDS);
+    }
+
+    for feature in features{
+        let mut gpu = GPUContextBuilder::new()
+            .set_features_util()
+            .enable_feature(feature)
+            .build();
+
+        let texture = TextureBuilder::new()
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .from_raw(vec![255; 16 * 16 * 4], wgpu::Extent3d{
+                width: 16,
+                height: 16,
+                depth_or_array_layers: 1,
+            })
+            .build(&gpu.device, &gpu.queue);
+
+        gpu.encode(|gpu, encoder|{
+            texture.clear(&gpu.device, encoder, wgpu::Color::TRANSPARENT).unwrap();
+        });
+        let data = pollster::block_on(texture.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+        assert!(data.iter().all(|x| *x == 0));
+
+        gpu.encode(|gpu, encoder|{
+            texture.clear(&gpu.device, encoder, wgpu::Color::BLUE).unwrap();
+        });
+        let data = pollster::block_on(texture.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+        assert!(data.chunks(4).all(|pixel| pixel == [0, 0, 255, 255]));
+
+        // Depth textures have no COPY_DST usage and are cleared through the depth attachment.
+        for format in [wgpu::TextureFormat::Depth32Float, wgpu::TextureFormat::Depth24PlusStencil8]{
+            let depth = TextureBuilder::new()
+                .clear([16, 16])
+                .depth(format)
+                .build_empty(&gpu.device);
+            gpu.encode(|gpu, encoder|{
+                depth.clear(&gpu.device, encoder, wgpu::Color::WHITE).unwrap();
+                depth.clear(&gpu.device, encoder, wgpu::Color::TRANSPARENT).unwrap();
+            });
+        }
+    }
+}
+
+#[test]
+fn clear_unsupported(){
+    let mut gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let volume = TextureBuilder::new()
+        .clear([4, 4, 4])
+        .dimension(wgpu::TextureDimension::D3)
+        .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+        .build_empty(&gpu.device);
+    let sampled = TextureBuilder::new()
+        .clear([4, 4])
+        .usage(wgpu::TextureUsages::TEXTURE_BINDING)
+        .build_empty(&gpu.device);
+
+    gpu.encode(|gpu, encoder|{
+        let err = volume.clear(&gpu.device, encoder, wgpu::Color::BLUE).unwrap_err();
+        assert!(err.to_string().contains("2D"), "{}", err);
+
+        let err = sampled.clear(&gpu.device, encoder, wgpu::Color::BLUE).unwrap_err();
+        assert!(err.to_string().contains("RENDER_ATTACHMENT"), "{}", err);
+    });
+}
+
+#[test]
+fn from_raw_block_size(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    for format in [wgpu::TextureFormat::R8Unorm, wgpu::TextureFormat::Rgba16Float]{
+        let len = (texture::bytes_per_pixel(format) * 64 * 64) as usize;
+        let texture = TextureBuilder::new()
+            .format(format)
+            .from_raw(vec![0; len], wgpu::Extent3d{
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            })
+            .build(&gpu.device, &gpu.queue);
+
+        let read = pollster::block_on(texture.read_to_vec(&gpu.device, &gpu.queue)).unwrap();
+        assert_eq!(read.len(), len);
+    }
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn from_raw_wrong_length(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    TextureBuilder::new()
+        .format(wgpu::TextureFormat::Rgba16Float)
+        .from_raw(vec![0; 64 * 64 * 4], wgpu::Extent3d{
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        })
+        .build(&gpu.device, &gpu.queue);
+}
+
+#[test]
+fn sample_count_keeps_usage(){
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let before = TextureBuilder::new()
+        .clear([16, 16])
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+        .sample_count(4)
+        .build_empty(&gpu.device);
+    let after = TextureBuilder::new()
+        .clear([16, 16])
+        .sample_count(4)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+        .build_empty(&gpu.device);
+
+    assert_eq!(before.usage, wgpu::TextureUsages::RENDER_ATTACHMENT);
+    assert_eq!(after.usage, wgpu::TextureUsages::RENDER_ATTACHMENT);
+}
@@ -0,0 +1,17 @@
+use ewgpu::*;
+
+#[test]
+fn uniform_buffer_size(){
+    assert_eq!(UniformBuffer::<[f32; 3]>::size(), 16);
+    assert_eq!(UniformBuffer::<[f32; 4]>::size(), 16);
+    assert_eq!(UniformBuffer::<[f32; 5]>::size(), 32);
+
+    let gpu = GPUContextBuilder::new()
+        .set_features_util()
+        .build();
+
+    let uniform = UniformBuffer::new(&gpu.device, &[1.0f32, 2.0, 3.0], None);
+    uniform.update(&gpu.queue, &[4.0, 5.0, 6.0]);
+
+    let _bound = uniform.into_bound(&gpu.device);
+}